@@ -294,6 +294,31 @@ Respond with JSON only:
   }
 }
 
+// Ask the app what to do with a result, so the extension and the app follow
+// the same action rules. Without the app the result is shown as neutralized.
+async function decideAction(result, platform) {
+  try {
+    const response = await fetch(`${TAURI_BRIDGE_URL}/decide`, {
+      method: 'POST',
+      headers: {
+        'Content-Type': 'application/json'
+      },
+      body: JSON.stringify({
+        techniques: result.techniques || [],
+        severity: result.severity || 0,
+        platform: platform || ''
+      }),
+      signal: AbortSignal.timeout(2000)
+    });
+    if (response.ok) {
+      return await response.json();
+    }
+  } catch (e) {
+    // App not reachable
+  }
+  return null;
+}
+
 // Message handler
 chrome.runtime.onMessage.addListener((message, sender, sendResponse) => {
   if (message.type === 'CHECK_STATUS') {
@@ -311,7 +336,8 @@ chrome.runtime.onMessage.addListener((message, sender, sendResponse) => {
             const newCount = (stored.processedCount || 0) + 1;
             await chrome.storage.local.set({ processedCount: newCount });
           }
-          sendResponse({ success: true, ...result });
+          const action = await decideAction(result, message.platform);
+          sendResponse({ success: true, ...result, action });
         })
         .catch(error => sendResponse({ success: false, error: error.message }));
     });
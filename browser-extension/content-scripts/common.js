@@ -127,11 +127,15 @@ function shouldProcess(text) {
 
 /**
  * Send content to background for neutralization
+ *
+ * The response carries `action`, the app's decision for this platform and
+ * the active profile, or null when the app can't be reached.
  */
 async function neutralize(content) {
+  const platform = detectPlatform();
   return new Promise((resolve, reject) => {
     chrome.runtime.sendMessage(
-      { type: 'NEUTRALIZE', content },
+      { type: 'NEUTRALIZE', content, platform: platform === 'unknown' ? '' : platform },
       response => {
         if (chrome.runtime.lastError) {
           reject(new Error(chrome.runtime.lastError.message));
@@ -378,6 +382,69 @@ function getSeverityIcon(severity) {
   return String.fromCodePoint(0x1F7E2); // Green circle
 }
 
+/**
+ * Put a wrapper behind a notice the reader can click to expand
+ */
+function createCollapsedNotice(wrapper) {
+  const notice = document.createElement('div');
+  notice.className = 'fw-collapsed';
+  notice.dataset.fwProcessed = 'true';
+
+  const expandBtn = document.createElement('button');
+  expandBtn.className = 'fw-expand-btn';
+  expandBtn.textContent = `${String.fromCodePoint(0x1F6E1)} Hidden by FeelingWise - click to show`;
+
+  wrapper.classList.add('fw-hidden');
+  expandBtn.addEventListener('click', (e) => {
+    e.stopPropagation();
+    wrapper.classList.remove('fw-hidden');
+    expandBtn.remove();
+  });
+
+  notice.appendChild(expandBtn);
+  notice.appendChild(wrapper);
+  return notice;
+}
+
+/**
+ * Apply a neutralization result the way the app decided
+ *
+ * - show_original: leave the page untouched
+ * - hide: remove `hideTarget`
+ * - collapse: neutralized wrapper behind a click-to-expand notice
+ * - show_neutralized: neutralized wrapper
+ *
+ * Without an action (app not reachable) results with techniques get the
+ * neutralized wrapper, as before.
+ *
+ * @param {object} result - Response from neutralize()
+ * @param {string} text - Original text content
+ * @param {object} originalStyles - Computed styles from the original element
+ * @param {function} replace - Puts the wrapper in place of the content
+ * @param {Element} hideTarget - Node removed for "hide"
+ * @returns {boolean} Whether the page was changed
+ */
+function applyResult(result, text, originalStyles, replace, hideTarget) {
+  const action = result.action ? result.action.action : null;
+  if (!action && !(result.techniques && result.techniques.length > 0)) return false;
+  if (action === 'show_original') return false;
+
+  if (action === 'hide') {
+    hideTarget.remove();
+    return true;
+  }
+
+  const wrapper = createNeutralizedWrapper(
+    text,
+    result.neutralized,
+    result.techniques,
+    result.severity,
+    originalStyles  // Pass captured styles for font matching
+  );
+  replace(action === 'collapse' ? createCollapsedNotice(wrapper) : wrapper);
+  return true;
+}
+
 /**
  * Escape HTML entities
  */
@@ -406,18 +473,8 @@ async function processElement(element, getText, setText) {
   try {
     const result = await neutralize(text);
 
-    if (result.techniques && result.techniques.length > 0) {
-      const wrapper = createNeutralizedWrapper(
-        text,
-        result.neutralized,
-        result.techniques,
-        result.severity,
-        originalStyles  // Pass captured styles for font matching
-      );
-
-      // Replace element content with wrapper
-      setText(element, wrapper);
-    }
+    // Replace element content with wrapper
+    applyResult(result, text, originalStyles, wrapper => setText(element, wrapper), element);
   } catch (error) {
     console.error('FeelingWise: Failed to process element', error);
     // Don't mark as processed on error so it can be retried
@@ -457,6 +514,7 @@ window.FW = {
   processElement,
   createObserver,
   createNeutralizedWrapper,
+  applyResult,
   processedElements,
   captureElementStyles,
   detectPlatform
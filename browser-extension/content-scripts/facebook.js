@@ -115,21 +115,14 @@
     try {
       const result = await window.FW.neutralize(text);
 
-      if (result.techniques && result.techniques.length > 0) {
-        textContainer.classList.remove('fw-processing');
-
-        const wrapper = window.FW.createNeutralizedWrapper(
-          text,
-          result.neutralized,
-          result.techniques,
-          result.severity,
-          originalStyles  // Pass captured styles for font matching
-        );
-
-        replacePostContent(textContainer, wrapper);
-      } else {
-        textContainer.classList.remove('fw-processing');
-      }
+      textContainer.classList.remove('fw-processing');
+      window.FW.applyResult(
+        result,
+        text,
+        originalStyles,
+        wrapper => replacePostContent(textContainer, wrapper),
+        postElement
+      );
     } catch (error) {
       console.error('FeelingWise: Failed to process Facebook post', error);
       textContainer.classList.remove('fw-processing');
@@ -82,21 +82,14 @@
     try {
       const result = await window.FW.neutralize(text);
 
-      if (result.techniques && result.techniques.length > 0) {
-        textElement.classList.remove('fw-processing');
-
-        const wrapper = window.FW.createNeutralizedWrapper(
-          text,
-          result.neutralized,
-          result.techniques,
-          result.severity,
-          originalStyles  // Pass captured styles for font matching
-        );
-
-        replaceTweetContent(textElement, wrapper);
-      } else {
-        textElement.classList.remove('fw-processing');
-      }
+      textElement.classList.remove('fw-processing');
+      window.FW.applyResult(
+        result,
+        text,
+        originalStyles,
+        wrapper => replaceTweetContent(textElement, wrapper),
+        tweetElement
+      );
     } catch (error) {
       console.error('FeelingWise: Failed to process tweet', error);
       textElement.classList.remove('fw-processing');
@@ -51,8 +51,12 @@
 
     try {
       const result = await window.FW.neutralize(text);
+      const action = result.action ? result.action.action : null;
 
-      if (result.techniques && result.techniques.length > 0) {
+      if (action === 'hide') {
+        titleElement.remove();
+      } else if (action !== 'show_original' && (action || (result.techniques && result.techniques.length > 0))) {
+        // Titles are too short to collapse; collapse shows the neutralized title
         titleElement.classList.remove('fw-processing');
 
         // For titles, we do inline replacement instead of full wrapper
@@ -122,22 +126,11 @@
     try {
       const result = await window.FW.neutralize(text);
 
-      if (result.techniques && result.techniques.length > 0) {
-        textElement.classList.remove('fw-processing');
-
-        const wrapper = window.FW.createNeutralizedWrapper(
-          text,
-          result.neutralized,
-          result.techniques,
-          result.severity,
-          originalStyles  // Pass captured styles for font matching
-        );
-
+      textElement.classList.remove('fw-processing');
+      window.FW.applyResult(result, text, originalStyles, wrapper => {
         textElement.innerHTML = '';
         textElement.appendChild(wrapper);
-      } else {
-        textElement.classList.remove('fw-processing');
-      }
+      }, commentElement);
     } catch (error) {
       console.error('FeelingWise: Failed to process YouTube comment', error);
       textElement.classList.remove('fw-processing');
@@ -165,22 +158,11 @@
     try {
       const result = await window.FW.neutralize(text);
 
-      if (result.techniques && result.techniques.length > 0) {
-        descElement.classList.remove('fw-processing');
-
-        const wrapper = window.FW.createNeutralizedWrapper(
-          text,
-          result.neutralized,
-          result.techniques,
-          result.severity,
-          originalStyles  // Pass captured styles for font matching
-        );
-
-        // Prepend wrapper to description
+      descElement.classList.remove('fw-processing');
+      // Prepend wrapper to description
+      window.FW.applyResult(result, text, originalStyles, wrapper => {
         descElement.insertBefore(wrapper, descElement.firstChild);
-      } else {
-        descElement.classList.remove('fw-processing');
-      }
+      }, descElement);
     } catch (error) {
       console.error('FeelingWise: Failed to process YouTube description', error);
       descElement.classList.remove('fw-processing');
@@ -6,6 +6,7 @@
  */
 
 import { invoke } from '@tauri-apps/api/core';
+import { listen, UnlistenFn } from '@tauri-apps/api/event';
 import { AnalysisResult, AgeGroup, Technique } from '../types';
 
 // ============================================================================
@@ -42,6 +43,17 @@ export interface RecommendedModel {
   description: string;
 }
 
+export type ContentKind = 'unknown' | 'post' | 'comment' | 'title';
+
+/** Where a piece of content was found */
+export interface ContentSource {
+  /** e.g. 'twitter', 'facebook', 'youtube' */
+  platform?: string;
+  kind?: ContentKind;
+  /** SHA-256 of the author's handle; a raw handle is hashed by the app */
+  author_hash?: string;
+}
+
 export interface CachedNeutralization {
   content_hash: string;
   original: string;
@@ -50,13 +62,150 @@ export interface CachedNeutralization {
   severity: number;
   created_at: number;
   hit_count: number;
+  model: string;
+  prompt_version: string;
+  persona: string;
+  source?: ContentSource;
+  /** 1 for an exact cache hit, below 1 for a near-duplicate hit */
+  similarity?: number;
+  /** What to do with the content under the policy in force */
+  action?: ActionDecision;
+}
+
+export type ContentAction = 'show_original' | 'show_neutralized' | 'collapse' | 'hide';
+
+/** Technique and severity range mapped to an action */
+export interface ActionRule {
+  /** Matched loosely ("fear appeal" matches "Fear Appeals"); empty means any */
+  techniques: string[];
+  /** Inclusive */
+  min_severity: number;
+  /** Inclusive */
+  max_severity: number;
+  action: ContentAction;
+}
+
+/** Ordered rules; the first match wins */
+export interface ActionPolicy {
+  rules: ActionRule[];
+  default_action: ContentAction;
+}
+
+export interface ActionDecision {
+  action: ContentAction;
+  /** Index of the matching rule; absent for the default action or content below the threshold */
+  rule?: number;
+}
+
+export interface HistoryQuery {
+  /** Words matched against originals and outputs */
+  text?: string;
+  technique?: string;
+  min_severity?: number;
+  max_severity?: number;
+  /** Unix seconds, inclusive */
+  from?: number;
+  /** Unix seconds, exclusive */
+  to?: number;
+  platform?: string;
+  kind?: ContentKind;
+  author_hash?: string;
+  offset?: number;
+  limit?: number;
+}
+
+export interface HistoryPage {
+  items: CachedNeutralization[];
+  total: number;
+  offset: number;
+  limit: number;
+}
+
+export type CacheInvalidation =
+  | { by: 'content'; value: string }
+  | { by: 'model'; value: string }
+  | { by: 'prompt_version'; value: string }
+  | { by: 'persona'; value: string };
+
+export type EventSource = 'cache' | 'fuzzy_cache' | 'model' | 'rule_based';
+
+export interface NamedCount {
+  name: string;
+  events: number;
+}
+
+export interface LatencyStat {
+  name: string;
+  events: number;
+  average_latency_ms: number;
+}
+
+/** Local hour (0-23) or weekday (0 = Sunday) */
+export interface TimeBucket {
+  bucket: number;
+  events: number;
+  total_severity: number;
+  average_severity: number;
+}
+
+export interface AnalyticsSummary {
+  days: number;
+  total_events: number;
+  average_severity: number;
+  by_platform: NamedCount[];
+  /** Events per profile id; events from before profiles count under '' */
+  by_profile: NamedCount[];
+  /** `name` is an EventSource */
+  by_source: LatencyStat[];
+  technique_totals: NamedCount[];
+  techniques_over_time: { day: string; technique: string; events: number }[];
+  severity_distribution: { severity: number; events: number }[];
+  /** Most manipulative first */
+  busiest_hours: TimeBucket[];
+  /** Most manipulative first */
+  busiest_weekdays: TimeBucket[];
+  average_inference_latency_ms: number;
+  latency_by_model: LatencyStat[];
 }
 
 export interface CacheStats {
   total_entries: number;
+  /** Exact plus near-duplicate hits */
   cache_hits: number;
+  exact_hits: number;
+  fuzzy_hits: number;
   cache_misses: number;
   hit_rate: number;
+  /** Entries held in the in-memory hot tier */
+  hot_entries: number;
+}
+
+export interface CacheUsage {
+  entries: number;
+  /** Database plus write-ahead log on disk */
+  file_bytes: number;
+  used_bytes: number;
+  max_entries: number;
+  max_bytes: number;
+}
+
+export interface GuardianStatus {
+  /** Whether a guardian PIN is set */
+  enabled: boolean;
+  /** The guardian file couldn't be read; everything stays locked until reset */
+  damaged: boolean;
+  /** Unix timestamp of the last reset of a damaged guardian file */
+  reset_at: number | null;
+  /** Seconds until unlocking is allowed again after too many failures */
+  locked_out_secs: number;
+  /** Unix timestamps of recent failed unlock attempts */
+  failed_attempts: number[];
+}
+
+export interface UnlockToken {
+  token: string;
+  /** Unix timestamp after which the token is rejected */
+  expires_at: number;
 }
 
 // ============================================================================
@@ -114,7 +263,7 @@ export async function stopOllama(): Promise<void> {
   if (!isTauri()) {
     throw new Error('Ollama management requires desktop app');
   }
-  return await invoke('stop_ollama');
+  return await invoke('stop_ollama', { guardianToken: guardianToken() });
 }
 
 export async function listOllamaModels(): Promise<string[]> {
@@ -141,12 +290,20 @@ export async function pullModel(modelName: string): Promise<void> {
   return await invoke('pull_model', { modelName });
 }
 
+/** Trust the model as installed now after it changed outside the app */
+export async function repinModel(): Promise<void> {
+  if (!isTauri()) {
+    throw new Error('Model management requires desktop app');
+  }
+  await invoke('repin_model', { guardianToken: guardianToken() });
+}
+
 // ============================================================================
 // NEW: Friendly Status & Setup APIs
 // ============================================================================
 
 export interface FriendlyStatus {
-  status: 'starting' | 'running' | 'not_installed' | 'model_missing' | 'error' | 'stopped';
+  status: 'starting' | 'running' | 'not_installed' | 'model_missing' | 'model_changed' | 'degraded' | 'error' | 'stopped';
   message: string;
   download_url?: string;
 }
@@ -159,12 +316,136 @@ export interface SetupStatus {
 }
 
 export interface AppSettings {
+  /** Settings file format; set by the app */
+  schema_version?: number;
   language: string;
   start_on_login: boolean;
   minimize_to_tray: boolean;
   first_run_complete: boolean;
+  /** A guardian PIN has been set; managed by the guardian commands */
+  guardian_enabled?: boolean;
   selected_model: string;
-  persona: string;
+  selected_model_digest: string | null;
+  auto_pull_model: boolean;
+  profiles: Profile[];
+  /** Id of the profile protection currently follows */
+  active_profile: string;
+  ollama_binary_path: string | null;
+  ollama_models_dir: string | null;
+  resource_policy?: ResourcePolicy;
+  cache_policy?: CachePolicy;
+  digest?: DigestSchedule;
+}
+
+export type SettingSource = 'default' | 'user' | 'policy';
+
+/** Settings in force; fields with source 'policy' are locked by an administrator */
+export interface EffectiveSettings {
+  settings: AppSettings;
+  sources: Record<string, SettingSource>;
+  /** Managed policy file in force, if any */
+  policy_path: string | null;
+}
+
+export interface ImportedSettings {
+  settings: AppSettings;
+  /** Public key (hex) that signed the file */
+  signer: string;
+  exported_at: number;
+  /** Fields that couldn't be read and kept their defaults */
+  dropped: string[];
+}
+
+export type Persona = 'child' | 'teen' | 'adult';
+
+/** One person's protection settings */
+export interface Profile {
+  id: string;
+  name: string;
+  persona: Persona;
+  /** Content scored below this severity (0-10) is shown unchanged */
+  severity_threshold: number;
+  /** Protected platforms; empty means all of them */
+  platforms: string[];
+  /** Weekly windows that override this profile; the first in force wins */
+  schedule: ScheduleWindow[];
+  /** Custom action rules; null uses the preset for the persona in force */
+  actions: ActionPolicy | null;
+}
+
+/** A weekly window that overrides parts of its profile, in local time */
+export interface ScheduleWindow {
+  name: string;
+  /** Days the window starts on, 0 = Sunday */
+  days: number[];
+  /** "HH:MM" */
+  start: string;
+  /** "HH:MM"; at or before start means the next day */
+  end: string;
+  persona: Persona | null;
+  severity_threshold: number | null;
+  /** Turn protection off for the window */
+  paused: boolean;
+}
+
+/** The active profile with any schedule window in force applied */
+export interface EffectivePolicy {
+  profile_id: string;
+  profile_name: string;
+  persona: Persona;
+  severity_threshold: number;
+  platforms: string[];
+  paused: boolean;
+  /** Name of the window in force */
+  window: string | null;
+  /** Unix time of the next window start or end */
+  next_change: number | null;
+  /** The profile's action rules, or the preset for the persona */
+  actions: ActionPolicy;
+}
+
+/** When to write the weekly digest report */
+export interface DigestSchedule {
+  enabled: boolean;
+  /** 0 = Sunday */
+  weekday: number;
+  /** Local hour, 0-23 */
+  hour: number;
+  /** Flag the tray icon when a new digest is ready */
+  notify: boolean;
+}
+
+export interface DigestFiles {
+  html: string;
+  markdown: string;
+}
+
+export type EvictionPolicy = 'lru' | 'lfu';
+
+export interface CachePolicy {
+  ttl_hours: number;
+  max_entries: number;
+  max_bytes: number;
+  eviction: EvictionPolicy;
+  prune_interval_secs: number;
+  /** Store only hashes and results, never the original post text */
+  privacy_mode: boolean;
+}
+
+export type GovernorMode = 'normal' | 'throttled' | 'rule_based' | 'paused';
+
+export interface ResourcePolicy {
+  enabled: boolean;
+  busy_cpu_percent: number;
+  min_available_ram_mb: number;
+  busy_action: GovernorMode;
+  on_battery_action: GovernorMode;
+  low_battery_percent: number;
+  low_battery_action: GovernorMode;
+  unload_model: boolean;
+  throttle_delay_ms: number;
+  check_interval_secs: number;
+  sustained_samples: number;
 }
 
 export async function getFriendlyStatus(): Promise<FriendlyStatus> {
@@ -194,7 +475,14 @@ export async function getAppSettings(): Promise<AppSettings> {
       minimize_to_tray: true,
       first_run_complete: false,
       selected_model: 'phi3:mini',
-      persona: 'adult',
+      selected_model_digest: null,
+      auto_pull_model: true,
+      profiles: [
+        { id: 'default', name: 'Default', persona: 'adult', severity_threshold: 5, platforms: [], schedule: [], actions: null },
+      ],
+      active_profile: 'default',
+      ollama_binary_path: null,
+      ollama_models_dir: null,
     };
   }
   return await invoke<AppSettings>('get_settings');
@@ -202,12 +490,66 @@ export async function getAppSettings(): Promise<AppSettings> {
 
 export async function saveAppSettings(settings: AppSettings): Promise<void> {
   if (!isTauri()) return;
-  return await invoke('save_settings', { newSettings: settings });
+  return await invoke('save_settings', { newSettings: settings, guardianToken: guardianToken() });
+}
+
+export async function getEffectivePolicy(): Promise<EffectivePolicy | null> {
+  if (!isTauri()) {
+    return null;
+  }
+  return await invoke<EffectivePolicy>('get_effective_policy');
+}
+
+export async function getEffectiveSettings(): Promise<EffectiveSettings> {
+  if (!isTauri()) {
+    return { settings: await getAppSettings(), sources: {}, policy_path: null };
+  }
+  return await invoke<EffectiveSettings>('get_effective_settings');
+}
+
+/** Write settings to a signed file; returns the signer's public key */
+export async function exportSettings(path: string): Promise<string> {
+  if (!isTauri()) {
+    throw new Error('Settings export requires desktop app');
+  }
+  return await invoke<string>('export_settings', { path });
+}
+
+/** Verify a signed settings file and apply it */
+export async function importSettings(path: string): Promise<ImportedSettings> {
+  if (!isTauri()) {
+    throw new Error('Settings import requires desktop app');
+  }
+  return await invoke<ImportedSettings>('import_settings', { path, guardianToken: guardianToken() });
+}
+
+/** Built-in action policies for child, teen and adult */
+export async function getActionPresets(): Promise<Record<Persona, ActionPolicy> | null> {
+  if (!isTauri()) {
+    return null;
+  }
+  return await invoke<Record<Persona, ActionPolicy>>('get_action_presets');
+}
+
+/** Switch profiles; needs the guardian PIN unlocked while one is set */
+export async function setActiveProfile(id: string): Promise<void> {
+  if (!isTauri()) return;
+  return await invoke('set_active_profile', { id, guardianToken: guardianToken() });
+}
+
+/** Called when settings change outside the settings screen: a reloaded file or a tray profile switch */
+export async function onSettingsChanged(
+  handler: (settings: AppSettings) => void
+): Promise<UnlistenFn> {
+  if (!isTauri()) {
+    return () => {};
+  }
+  return await listen<AppSettings>('settings-changed', event => handler(event.payload));
 }
 
 export async function completeFirstRun(): Promise<void> {
   if (!isTauri()) return;
-  return await invoke('complete_first_run');
+  return await invoke('complete_first_run', { guardianToken: guardianToken() });
 }
 
 export async function openOllamaDownload(): Promise<void> {
@@ -233,18 +575,128 @@ export async function restartOllama(): Promise<void> {
   return await invoke('restart_ollama');
 }
 
+// ============================================================================
+// GUARDIAN LOCK
+// ============================================================================
+
+let unlockToken: UnlockToken | null = null;
+
+/** Current unlock token, passed to privileged commands */
+function guardianToken(): string | null {
+  if (unlockToken && unlockToken.expires_at * 1000 <= Date.now()) {
+    unlockToken = null;
+  }
+  return unlockToken?.token ?? null;
+}
+
+export async function getGuardianStatus(): Promise<GuardianStatus> {
+  if (!isTauri()) {
+    return { enabled: false, damaged: false, reset_at: null, locked_out_secs: 0, failed_attempts: [] };
+  }
+  return await invoke<GuardianStatus>('get_guardian_status');
+}
+
+/** Set the PIN, or change it while unlocked */
+export async function setGuardianPin(pin: string): Promise<void> {
+  if (!isTauri()) return;
+  await invoke('set_guardian_pin', { pin, guardianToken: guardianToken() });
+  unlockToken = null;
+}
+
+export async function clearGuardianPin(): Promise<void> {
+  if (!isTauri()) return;
+  await invoke('clear_guardian_pin', { guardianToken: guardianToken() });
+  unlockToken = null;
+}
+
+/** Set a new PIN when the guardian file is damaged */
+export async function resetGuardianPin(pin: string): Promise<void> {
+  if (!isTauri()) return;
+  await invoke('reset_guardian_pin', { pin });
+  unlockToken = null;
+}
+
+/** Unlock privileged actions for a few minutes */
+export async function unlockGuardian(pin: string): Promise<UnlockToken> {
+  if (!isTauri()) {
+    throw new Error('Guardian lock requires desktop app');
+  }
+  unlockToken = await invoke<UnlockToken>('unlock_guardian', { pin });
+  return unlockToken;
+}
+
+export async function lockGuardian(): Promise<void> {
+  unlockToken = null;
+  if (!isTauri()) return;
+  await invoke('lock_guardian');
+}
+
+/** Quit the app; needs an unlock token while a PIN is set */
+export async function quitApp(): Promise<void> {
+  if (!isTauri()) return;
+  await invoke('quit_app', { guardianToken: guardianToken() });
+}
+
+/** Called when a tray action needs the guardian PIN, with the action name */
+export async function onGuardianRequired(
+  handler: (action: string) => void
+): Promise<UnlistenFn> {
+  if (!isTauri()) {
+    return () => {};
+  }
+  return await listen<string>('guardian-required', event => handler(event.payload));
+}
+
 // ============================================================================
 // NEUTRALIZATION
 // ============================================================================
 
 export async function neutralizeContent(
   content: string,
-  model?: string
+  model?: string,
+  source?: ContentSource
 ): Promise<CachedNeutralization> {
   if (!isTauri()) {
     return mockNeutralization(content);
   }
-  return await invoke<CachedNeutralization>('neutralize_content', { content, model });
+  return await invoke<CachedNeutralization>('neutralize_content', { content, model, source });
+}
+
+// ============================================================================
+// ANALYTICS
+// ============================================================================
+
+export async function getAnalytics(days?: number): Promise<AnalyticsSummary | null> {
+  if (!isTauri()) {
+    return null;
+  }
+  return await invoke<AnalyticsSummary>('get_analytics', { days });
+}
+
+export async function clearAnalytics(): Promise<void> {
+  if (!isTauri()) {
+    return;
+  }
+  await invoke('clear_analytics', { guardianToken: guardianToken() });
+}
+
+// ============================================================================
+// REPORTS
+// ============================================================================
+
+/** Write a digest of the last seven days now */
+export async function generateDigest(): Promise<DigestFiles | null> {
+  if (!isTauri()) {
+    return null;
+  }
+  return await invoke<DigestFiles>('generate_digest');
+}
+
+export async function openLatestDigest(): Promise<void> {
+  if (!isTauri()) {
+    return;
+  }
+  await invoke('open_latest_digest');
 }
 
 // ============================================================================
@@ -253,16 +705,94 @@ export async function neutralizeContent(
 
 export async function getCacheStats(): Promise<CacheStats> {
   if (!isTauri()) {
-    return { total_entries: 0, cache_hits: 0, cache_misses: 0, hit_rate: 0 };
+    return { total_entries: 0, cache_hits: 0, exact_hits: 0, fuzzy_hits: 0, cache_misses: 0, hit_rate: 0, hot_entries: 0 };
   }
   return await invoke<CacheStats>('get_cache_stats');
 }
 
+export async function getCacheUsage(): Promise<CacheUsage | null> {
+  if (!isTauri()) {
+    return null;
+  }
+  return await invoke<CacheUsage>('get_cache_usage');
+}
+
+/** Past results, newest first; e.g. recent items for the Analyze tab */
+export async function searchHistory(query: HistoryQuery = {}): Promise<HistoryPage> {
+  if (!isTauri()) {
+    return { items: [], total: 0, offset: query.offset ?? 0, limit: query.limit ?? 50 };
+  }
+  return await invoke<HistoryPage>('search_history', { query });
+}
+
+export interface ImportReport {
+  imported: number;
+  kept_local: number;
+  skipped_incompatible: number;
+  skipped_expired: number;
+  skipped_invalid: number;
+}
+
+/** Write cached results (never originals) to a JSONL file; returns the entry count */
+export async function exportCache(path: string): Promise<number> {
+  return await invoke<number>('export_cache', { path });
+}
+
+export async function importCache(path: string): Promise<ImportReport> {
+  return await invoke<ImportReport>('import_cache', { path, guardianToken: guardianToken() });
+}
+
+export interface EncryptionStatus {
+  enabled: boolean;
+  unlocked: boolean;
+}
+
+export async function getCacheEncryptionStatus(): Promise<EncryptionStatus> {
+  if (!isTauri()) {
+    return { enabled: false, unlocked: false };
+  }
+  return await invoke<EncryptionStatus>('get_cache_encryption_status');
+}
+
+export async function enableCacheEncryption(passphrase: string): Promise<void> {
+  return await invoke('enable_cache_encryption', { passphrase, guardianToken: guardianToken() });
+}
+
+export async function unlockCache(passphrase: string): Promise<void> {
+  return await invoke('unlock_cache', { passphrase });
+}
+
+export async function lockCache(): Promise<void> {
+  return await invoke('lock_cache');
+}
+
+export async function rotateCacheKey(passphrase: string, newPassphrase?: string): Promise<void> {
+  return await invoke('rotate_cache_key', { passphrase, newPassphrase, guardianToken: guardianToken() });
+}
+
+export async function disableCacheEncryption(passphrase: string): Promise<void> {
+  return await invoke('disable_cache_encryption', { passphrase, guardianToken: guardianToken() });
+}
+
+export async function wipeCache(): Promise<void> {
+  if (!isTauri()) {
+    return;
+  }
+  return await invoke('wipe_cache', { guardianToken: guardianToken() });
+}
+
 export async function clearCache(): Promise<void> {
   if (!isTauri()) {
     return;
   }
-  return await invoke('clear_cache');
+  return await invoke('clear_cache', { guardianToken: guardianToken() });
+}
+
+export async function invalidateCache(target: CacheInvalidation): Promise<number> {
+  if (!isTauri()) {
+    return 0;
+  }
+  return await invoke<number>('invalidate_cache', { target, guardianToken: guardianToken() });
 }
 
 // ============================================================================
@@ -476,6 +1006,9 @@ function mockNeutralization(content: string): CachedNeutralization {
     severity: Math.min(10, techniques.length * 2),
     created_at: Date.now(),
     hit_count: 0,
+    model: 'mock',
+    prompt_version: 'mock',
+    persona: 'adult',
   };
 }
 
@@ -0,0 +1,183 @@
+//! Content Actions Module
+//!
+//! Decides what the extension and the app do with each result: show the
+//! original, show the neutralized text, collapse it behind a notice, or hide
+//! it. Rules match techniques and severity ranges; the decision is made here
+//! so every surface behaves the same.
+
+use serde::{Deserialize, Serialize};
+
+/// What to do with a piece of content
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentAction {
+    ShowOriginal,
+    ShowNeutralized,
+    /// Neutralized text behind a notice the reader can expand
+    Collapse,
+    Hide,
+}
+
+/// Technique and severity range mapped to an action
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ActionRule {
+    /// Techniques the rule applies to, matched loosely ("fear appeal"
+    /// matches "Fear Appeals"); empty means any content
+    pub techniques: Vec<String>,
+    /// Lowest severity the rule applies to, inclusive
+    pub min_severity: i32,
+    /// Highest severity the rule applies to, inclusive
+    pub max_severity: i32,
+    pub action: ContentAction,
+}
+
+impl Default for ActionRule {
+    fn default() -> Self {
+        Self {
+            techniques: Vec::new(),
+            min_severity: 0,
+            max_severity: 10,
+            action: ContentAction::ShowNeutralized,
+        }
+    }
+}
+
+impl ActionRule {
+    fn matches(&self, techniques: &[String], severity: i32) -> bool {
+        (self.min_severity..=self.max_severity).contains(&severity)
+            && (self.techniques.is_empty()
+                || self.techniques.iter().any(|wanted| {
+                    techniques.iter().any(|found| technique_matches(wanted, found))
+                }))
+    }
+}
+
+/// Whether `found` (as reported by the model) is the technique `wanted`,
+/// ignoring case, spacing and punctuation
+fn technique_matches(wanted: &str, found: &str) -> bool {
+    let normalize = |s: &str| -> String {
+        s.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect()
+    };
+    let wanted = normalize(wanted);
+    !wanted.is_empty() && normalize(found).contains(&wanted)
+}
+
+/// Ordered action rules; the first match wins
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ActionPolicy {
+    pub rules: Vec<ActionRule>,
+    /// Action when no rule matches
+    pub default_action: ContentAction,
+}
+
+impl Default for ActionPolicy {
+    fn default() -> Self {
+        Self::preset("adult")
+    }
+}
+
+/// Action chosen for one result
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActionDecision {
+    pub action: ContentAction,
+    /// Index of the rule that matched; absent for the default action or
+    /// content below the severity threshold
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule: Option<usize>,
+}
+
+impl ActionPolicy {
+    /// Built-in policy for a persona; unknown personas get the adult one
+    pub fn preset(persona: &str) -> Self {
+        let rule = |techniques: &[&str], min_severity: i32, action: ContentAction| ActionRule {
+            techniques: techniques.iter().map(|t| t.to_string()).collect(),
+            min_severity,
+            max_severity: 10,
+            action,
+        };
+
+        let rules = match persona {
+            "child" => vec![
+                rule(&["fear appeal", "ad hominem"], 5, ContentAction::Hide),
+                rule(&[], 7, ContentAction::Hide),
+                rule(&[], 4, ContentAction::Collapse),
+            ],
+            "teen" => vec![
+                rule(&["fear appeal"], 7, ContentAction::Collapse),
+                rule(&[], 8, ContentAction::Collapse),
+            ],
+            _ => vec![rule(&[], 9, ContentAction::Collapse)],
+        };
+
+        Self {
+            rules,
+            default_action: ContentAction::ShowNeutralized,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        for (index, rule) in self.rules.iter().enumerate() {
+            if !(0..=10).contains(&rule.min_severity)
+                || !(0..=10).contains(&rule.max_severity)
+                || rule.min_severity > rule.max_severity
+            {
+                return Err(format!(
+                    "Action rule {} needs a severity range within 0-10",
+                    index + 1
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Action for a result. Content below `threshold` is always shown as is.
+    pub fn decide(&self, techniques: &[String], severity: i32, threshold: i32) -> ActionDecision {
+        if severity < threshold {
+            return ActionDecision { action: ContentAction::ShowOriginal, rule: None };
+        }
+
+        match self.rules.iter().position(|rule| rule.matches(techniques, severity)) {
+            Some(index) => ActionDecision { action: self.rules[index].action, rule: Some(index) },
+            None => ActionDecision { action: self.default_action, rule: None },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn techniques(names: &[&str]) -> Vec<String> {
+        names.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn test_presets_and_rule_order() {
+        let child = ActionPolicy::preset("child");
+        let teen = ActionPolicy::preset("teen");
+        let adult = ActionPolicy::preset("adult");
+        assert!(child.validate().is_ok());
+
+        let fear = techniques(&["Fear Appeals", "All Caps"]);
+        assert_eq!(child.decide(&fear, 5, 1), ActionDecision { action: ContentAction::Hide, rule: Some(0) });
+        assert_eq!(child.decide(&techniques(&["All Caps"]), 5, 1).action, ContentAction::Collapse);
+        assert_eq!(child.decide(&techniques(&["All Caps"]), 2, 1).action, ContentAction::ShowNeutralized);
+        assert_eq!(child.decide(&fear, 0, 1).action, ContentAction::ShowOriginal);
+
+        assert_eq!(teen.decide(&fear, 7, 3).action, ContentAction::Collapse);
+        assert_eq!(teen.decide(&techniques(&["All Caps"]), 7, 3).action, ContentAction::ShowNeutralized);
+        assert_eq!(adult.decide(&fear, 8, 5), ActionDecision { action: ContentAction::ShowNeutralized, rule: None });
+        assert_eq!(adult.decide(&fear, 4, 5).action, ContentAction::ShowOriginal);
+
+        let bad = ActionPolicy {
+            rules: vec![ActionRule { min_severity: 8, max_severity: 3, ..Default::default() }],
+            ..Default::default()
+        };
+        assert!(bad.validate().is_err());
+    }
+}
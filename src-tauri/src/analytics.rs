@@ -0,0 +1,635 @@
+//! Analytics Module
+//!
+//! Records one event per neutralization (never the content itself) and
+//! keeps per-day rollups next to the raw events, so the Stats tab can chart
+//! techniques, severity, busy hours and latency without scanning every event.
+
+use rusqlite::{Connection, params};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Raw events older than this are dropped; the daily rollups are kept
+const EVENT_RETENTION_DAYS: i64 = 90;
+/// Days covered by a summary unless the caller asks otherwise
+const DEFAULT_SUMMARY_DAYS: u32 = 30;
+
+/// Where a neutralization result came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventSource {
+    /// Exact cache hit
+    Cache,
+    /// Near-duplicate cache hit
+    FuzzyCache,
+    /// Fresh result from the local model
+    Model,
+    /// Rule-based fallback while the governor holds back the model
+    RuleBased,
+}
+
+impl EventSource {
+    fn as_str(&self) -> &'static str {
+        match self {
+            EventSource::Cache => "cache",
+            EventSource::FuzzyCache => "fuzzy_cache",
+            EventSource::Model => "model",
+            EventSource::RuleBased => "rule_based",
+        }
+    }
+}
+
+/// One neutralization, as recorded for analytics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NeutralizationEvent {
+    /// Unix seconds
+    pub timestamp: i64,
+    /// Site the content came from, empty if unknown
+    pub platform: String,
+    pub techniques: Vec<String>,
+    pub severity: i32,
+    /// Time from request to result
+    pub latency_ms: u64,
+    pub model: String,
+    pub source: EventSource,
+    /// Id of the profile active at the time, empty if unknown
+    #[serde(default)]
+    pub profile: String,
+}
+
+/// Events per name (technique, platform, ...)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamedCount {
+    pub name: String,
+    pub events: i64,
+}
+
+/// Events using a technique on one local day (`YYYY-MM-DD`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TechniqueDay {
+    pub day: String,
+    pub technique: String,
+    pub events: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeverityCount {
+    pub severity: i32,
+    pub events: i64,
+}
+
+/// Activity in one local hour of the day (0-23) or weekday (0 = Sunday)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeBucket {
+    pub bucket: u32,
+    pub events: i64,
+    /// Sum of severities; what "most manipulative" ranks by
+    pub total_severity: i64,
+    pub average_severity: f64,
+}
+
+/// Average latency for one model or source
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatencyStat {
+    pub name: String,
+    pub events: i64,
+    pub average_latency_ms: f64,
+}
+
+/// Events and average severity on one local day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DayTotal {
+    pub day: String,
+    pub events: i64,
+    pub average_severity: f64,
+}
+
+/// Aggregates over the last `days` local days, today included
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalyticsSummary {
+    pub days: u32,
+    pub total_events: i64,
+    pub average_severity: f64,
+    pub by_platform: Vec<NamedCount>,
+    pub by_profile: Vec<NamedCount>,
+    pub by_source: Vec<LatencyStat>,
+    /// Most used first
+    pub technique_totals: Vec<NamedCount>,
+    /// Oldest day first
+    pub techniques_over_time: Vec<TechniqueDay>,
+    pub severity_distribution: Vec<SeverityCount>,
+    /// Most manipulative first
+    pub busiest_hours: Vec<TimeBucket>,
+    /// Most manipulative first
+    pub busiest_weekdays: Vec<TimeBucket>,
+    /// Average over fresh model results only; cache hits would hide it
+    pub average_inference_latency_ms: f64,
+    pub latency_by_model: Vec<LatencyStat>,
+}
+
+// ============================================================================
+// SCHEMA MIGRATIONS
+// ============================================================================
+
+type Migration = fn(&Connection) -> rusqlite::Result<()>;
+
+/// Ordered schema migrations; entry `i` upgrades `user_version` `i` to
+/// `i + 1`. Released migrations are never edited, only appended to.
+const MIGRATIONS: &[Migration] = &[migrate_v1_events, migrate_v2_profiles];
+
+/// v1: raw events and daily rollups. Rollup days and hours are local time.
+fn migrate_v1_events(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS events (
+            id INTEGER PRIMARY KEY,
+            timestamp INTEGER NOT NULL,
+            platform TEXT NOT NULL DEFAULT '',
+            techniques TEXT NOT NULL,
+            severity INTEGER NOT NULL,
+            latency_ms INTEGER NOT NULL,
+            model TEXT NOT NULL,
+            source TEXT NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_events_timestamp ON events(timestamp);
+
+        CREATE TABLE IF NOT EXISTS daily_totals (
+            day TEXT NOT NULL,
+            platform TEXT NOT NULL,
+            model TEXT NOT NULL,
+            source TEXT NOT NULL,
+            events INTEGER NOT NULL DEFAULT 0,
+            severity_sum INTEGER NOT NULL DEFAULT 0,
+            latency_sum INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (day, platform, model, source)
+        );
+
+        CREATE TABLE IF NOT EXISTS daily_techniques (
+            day TEXT NOT NULL,
+            technique TEXT NOT NULL,
+            events INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (day, technique)
+        );
+
+        CREATE TABLE IF NOT EXISTS daily_severity (
+            day TEXT NOT NULL,
+            severity INTEGER NOT NULL,
+            events INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (day, severity)
+        );
+
+        CREATE TABLE IF NOT EXISTS daily_hours (
+            day TEXT NOT NULL,
+            hour INTEGER NOT NULL,
+            events INTEGER NOT NULL DEFAULT 0,
+            severity_sum INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (day, hour)
+        );
+        "#,
+    )
+}
+
+/// v2: events record the active profile, with a rollup per profile. Older
+/// events count under the empty profile.
+fn migrate_v2_profiles(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        ALTER TABLE events ADD COLUMN profile TEXT NOT NULL DEFAULT '';
+
+        CREATE TABLE IF NOT EXISTS daily_profiles (
+            day TEXT NOT NULL,
+            profile TEXT NOT NULL,
+            events INTEGER NOT NULL DEFAULT 0,
+            severity_sum INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (day, profile)
+        );
+        "#,
+    )
+}
+
+/// Bring a database up to the latest schema, one transaction per migration
+fn migrate(conn: &mut Connection) -> Result<(), String> {
+    let err = |e: rusqlite::Error| format!("Failed to initialize analytics schema: {}", e);
+    let version: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0)).map_err(err)?;
+    if version > MIGRATIONS.len() {
+        return Err(format!(
+            "Analytics schema version {} is newer than supported version {}",
+            version,
+            MIGRATIONS.len()
+        ));
+    }
+
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let tx = conn.transaction().map_err(err)?;
+        migration(&tx)
+            .and_then(|_| tx.pragma_update(None, "user_version", index as i64 + 1))
+            .and_then(|_| tx.commit())
+            .map_err(err)?;
+    }
+    Ok(())
+}
+
+/// First local day (`YYYY-MM-DD`) of a range of `days` days ending today
+fn first_day(conn: &Connection, days: u32) -> rusqlite::Result<String> {
+    conn.query_row(
+        "SELECT date('now', 'localtime', ?1)",
+        params![format!("-{} days", days.max(1) - 1)],
+        |row| row.get(0),
+    )
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// SQLite-backed event log with daily rollups.
+///
+/// Like the cache, methods block on SQLite; async code goes through
+/// [`Self::run`]. Clones share the same database.
+#[derive(Clone)]
+pub struct Analytics {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl Analytics {
+    pub fn new(db_path: Option<PathBuf>) -> Result<Self, String> {
+        let path = db_path.unwrap_or_else(|| {
+            directories::ProjectDirs::from("com", "feelingwise", "FeelingWise")
+                .map(|dirs| dirs.data_dir().join("analytics.db"))
+                .unwrap_or_else(|| PathBuf::from("analytics.db"))
+        });
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create analytics directory: {}", e))?;
+        }
+
+        let conn = Connection::open(&path)
+            .and_then(|conn| {
+                let _: String = conn.query_row("PRAGMA journal_mode = WAL", [], |row| row.get(0))?;
+                conn.pragma_update(None, "synchronous", "NORMAL")?;
+                Ok(conn)
+            })
+            .map_err(|e| format!("Failed to open analytics database: {}", e))?;
+
+        let analytics = Self::from_connection(conn)?;
+        if let Err(e) = analytics.prune() {
+            log::warn!("Failed to prune analytics events: {}", e);
+        }
+        Ok(analytics)
+    }
+
+    /// Event log that lives only for this run
+    pub fn in_memory() -> Result<Self, String> {
+        let conn = Connection::open_in_memory()
+            .map_err(|e| format!("Failed to open in-memory analytics: {}", e))?;
+        Self::from_connection(conn)
+    }
+
+    fn from_connection(mut conn: Connection) -> Result<Self, String> {
+        migrate(&mut conn)?;
+        Ok(Self { conn: Arc::new(Mutex::new(conn)) })
+    }
+
+    /// Run blocking analytics work on the blocking thread pool
+    pub async fn run<T, F>(&self, work: F) -> Result<T, String>
+    where
+        F: FnOnce(&Analytics) -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let analytics = self.clone();
+        tokio::task::spawn_blocking(move || work(&analytics))
+            .await
+            .map_err(|e| format!("Analytics worker failed: {}", e))
+    }
+
+    /// Store an event and add it to the rollups
+    pub fn record(&self, event: &NeutralizationEvent) -> Result<(), String> {
+        let techniques = serde_json::to_string(&event.techniques)
+            .map_err(|e| format!("Failed to serialize techniques: {}", e))?;
+        let latency = event.latency_ms as i64;
+
+        let mut conn = self.conn.lock()
+            .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+        let write = |tx: &rusqlite::Transaction| -> rusqlite::Result<()> {
+            let (day, hour): (String, i64) = tx.query_row(
+                "SELECT date(?1, 'unixepoch', 'localtime'),
+                        CAST(strftime('%H', ?1, 'unixepoch', 'localtime') AS INTEGER)",
+                params![event.timestamp],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )?;
+
+            tx.execute(
+                "INSERT INTO events (timestamp, platform, techniques, severity, latency_ms, model, source, profile)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    event.timestamp,
+                    event.platform,
+                    techniques,
+                    event.severity,
+                    latency,
+                    event.model,
+                    event.source.as_str(),
+                    event.profile
+                ],
+            )?;
+
+            tx.execute(
+                "INSERT INTO daily_totals (day, platform, model, source, events, severity_sum, latency_sum)
+                 VALUES (?1, ?2, ?3, ?4, 1, ?5, ?6)
+                 ON CONFLICT (day, platform, model, source) DO UPDATE SET
+                     events = events + 1,
+                     severity_sum = severity_sum + excluded.severity_sum,
+                     latency_sum = latency_sum + excluded.latency_sum",
+                params![day, event.platform, event.model, event.source.as_str(), event.severity, latency],
+            )?;
+            tx.execute(
+                "INSERT INTO daily_profiles (day, profile, events, severity_sum) VALUES (?1, ?2, 1, ?3)
+                 ON CONFLICT (day, profile) DO UPDATE SET
+                     events = events + 1,
+                     severity_sum = severity_sum + excluded.severity_sum",
+                params![day, event.profile, event.severity],
+            )?;
+            tx.execute(
+                "INSERT INTO daily_severity (day, severity, events) VALUES (?1, ?2, 1)
+                 ON CONFLICT (day, severity) DO UPDATE SET events = events + 1",
+                params![day, event.severity],
+            )?;
+            tx.execute(
+                "INSERT INTO daily_hours (day, hour, events, severity_sum) VALUES (?1, ?2, 1, ?3)
+                 ON CONFLICT (day, hour) DO UPDATE SET
+                     events = events + 1,
+                     severity_sum = severity_sum + excluded.severity_sum",
+                params![day, hour, event.severity],
+            )?;
+            for technique in &event.techniques {
+                tx.execute(
+                    "INSERT INTO daily_techniques (day, technique, events) VALUES (?1, ?2, 1)
+                     ON CONFLICT (day, technique) DO UPDATE SET events = events + 1",
+                    params![day, technique],
+                )?;
+            }
+            Ok(())
+        };
+
+        let tx = conn.transaction()
+            .map_err(|e| format!("Failed to record event: {}", e))?;
+        write(&tx)
+            .and_then(|_| tx.commit())
+            .map_err(|e| format!("Failed to record event: {}", e))
+    }
+
+    /// Aggregates over the last `days` local days (default 30), from rollups
+    pub fn summary(&self, days: Option<u32>) -> Result<AnalyticsSummary, String> {
+        let days = days.unwrap_or(DEFAULT_SUMMARY_DAYS).max(1);
+        let conn = self.conn.lock()
+            .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+        let err = |e: rusqlite::Error| format!("Failed to read analytics: {}", e);
+
+        let first_day = first_day(&conn, days).map_err(err)?;
+
+        let (total_events, severity_sum): (i64, i64) = conn
+            .query_row(
+                "SELECT COALESCE(SUM(events), 0), COALESCE(SUM(severity_sum), 0)
+                 FROM daily_totals WHERE day >= ?1",
+                params![first_day],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .map_err(err)?;
+
+        let named = |sql: &str| -> Result<Vec<NamedCount>, String> {
+            let mut stmt = conn.prepare(sql).map_err(err)?;
+            let rows = stmt
+                .query_map(params![first_day], |row| {
+                    Ok(NamedCount { name: row.get(0)?, events: row.get(1)? })
+                })
+                .and_then(|rows| rows.collect())
+                .map_err(err);
+            rows
+        };
+        let latency = |sql: &str| -> Result<Vec<LatencyStat>, String> {
+            let mut stmt = conn.prepare(sql).map_err(err)?;
+            let rows = stmt
+                .query_map(params![first_day], |row| {
+                    Ok(LatencyStat {
+                        name: row.get(0)?,
+                        events: row.get(1)?,
+                        average_latency_ms: row.get(2)?,
+                    })
+                })
+                .and_then(|rows| rows.collect())
+                .map_err(err);
+            rows
+        };
+        let buckets = |sql: &str| -> Result<Vec<TimeBucket>, String> {
+            let mut stmt = conn.prepare(sql).map_err(err)?;
+            let rows = stmt
+                .query_map(params![first_day], |row| {
+                    let events: i64 = row.get(1)?;
+                    let total_severity: i64 = row.get(2)?;
+                    Ok(TimeBucket {
+                        bucket: row.get(0)?,
+                        events,
+                        total_severity,
+                        average_severity: total_severity as f64 / events.max(1) as f64,
+                    })
+                })
+                .and_then(|rows| rows.collect())
+                .map_err(err);
+            rows
+        };
+
+        let by_platform = named(
+            "SELECT platform, SUM(events) FROM daily_totals WHERE day >= ?1
+             GROUP BY platform ORDER BY SUM(events) DESC",
+        )?;
+        let by_profile = named(
+            "SELECT profile, SUM(events) FROM daily_profiles WHERE day >= ?1
+             GROUP BY profile ORDER BY SUM(events) DESC",
+        )?;
+        let technique_totals = named(
+            "SELECT technique, SUM(events) FROM daily_techniques WHERE day >= ?1
+             GROUP BY technique ORDER BY SUM(events) DESC, technique",
+        )?;
+        let by_source = latency(
+            "SELECT source, SUM(events), CAST(SUM(latency_sum) AS REAL) / SUM(events)
+             FROM daily_totals WHERE day >= ?1 GROUP BY source ORDER BY SUM(events) DESC",
+        )?;
+        let latency_by_model = latency(
+            "SELECT model, SUM(events), CAST(SUM(latency_sum) AS REAL) / SUM(events)
+             FROM daily_totals WHERE day >= ?1 AND source = 'model'
+             GROUP BY model ORDER BY SUM(events) DESC",
+        )?;
+        let busiest_hours = buckets(
+            "SELECT hour, SUM(events), SUM(severity_sum) FROM daily_hours WHERE day >= ?1
+             GROUP BY hour ORDER BY SUM(severity_sum) DESC, hour",
+        )?;
+        let busiest_weekdays = buckets(
+            "SELECT CAST(strftime('%w', day) AS INTEGER), SUM(events), SUM(severity_sum)
+             FROM daily_hours WHERE day >= ?1
+             GROUP BY 1 ORDER BY SUM(severity_sum) DESC, 1",
+        )?;
+
+        let techniques_over_time = {
+            let mut stmt = conn
+                .prepare(
+                    "SELECT day, technique, events FROM daily_techniques WHERE day >= ?1
+                     ORDER BY day, events DESC, technique",
+                )
+                .map_err(err)?;
+            let rows = stmt
+                .query_map(params![first_day], |row| {
+                    Ok(TechniqueDay { day: row.get(0)?, technique: row.get(1)?, events: row.get(2)? })
+                })
+                .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+                .map_err(err)?;
+            rows
+        };
+
+        let severity_distribution = {
+            let mut stmt = conn
+                .prepare(
+                    "SELECT severity, SUM(events) FROM daily_severity WHERE day >= ?1
+                     GROUP BY severity ORDER BY severity",
+                )
+                .map_err(err)?;
+            let rows = stmt
+                .query_map(params![first_day], |row| {
+                    Ok(SeverityCount { severity: row.get(0)?, events: row.get(1)? })
+                })
+                .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+                .map_err(err)?;
+            rows
+        };
+
+        let (model_events, model_latency): (i64, i64) = conn
+            .query_row(
+                "SELECT COALESCE(SUM(events), 0), COALESCE(SUM(latency_sum), 0)
+                 FROM daily_totals WHERE day >= ?1 AND source = 'model'",
+                params![first_day],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .map_err(err)?;
+
+        Ok(AnalyticsSummary {
+            days,
+            total_events,
+            average_severity: severity_sum as f64 / total_events.max(1) as f64,
+            by_platform,
+            by_profile,
+            by_source,
+            technique_totals,
+            techniques_over_time,
+            severity_distribution,
+            busiest_hours,
+            busiest_weekdays,
+            average_inference_latency_ms: model_latency as f64 / model_events.max(1) as f64,
+            latency_by_model,
+        })
+    }
+
+    /// Totals per local day over the last `days` days, oldest first. Days
+    /// without events are left out.
+    pub fn daily_trend(&self, days: u32) -> Result<Vec<DayTotal>, String> {
+        let conn = self.conn.lock()
+            .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+        let err = |e: rusqlite::Error| format!("Failed to read analytics: {}", e);
+
+        let first_day = first_day(&conn, days).map_err(err)?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT day, SUM(events), CAST(SUM(severity_sum) AS REAL) / SUM(events)
+                 FROM daily_totals WHERE day >= ?1 GROUP BY day ORDER BY day",
+            )
+            .map_err(err)?;
+        let rows = stmt
+            .query_map(params![first_day], |row| {
+                Ok(DayTotal { day: row.get(0)?, events: row.get(1)?, average_severity: row.get(2)? })
+            })
+            .and_then(|rows| rows.collect())
+            .map_err(err);
+        rows
+    }
+
+    /// Drop raw events past retention. Returns the number removed.
+    pub fn prune(&self) -> Result<usize, String> {
+        let cutoff = now() - EVENT_RETENTION_DAYS * 24 * 60 * 60;
+        let conn = self.conn.lock()
+            .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+        conn.execute("DELETE FROM events WHERE timestamp < ?1", params![cutoff])
+            .map_err(|e| format!("Failed to prune analytics events: {}", e))
+    }
+
+    /// Delete all events and rollups
+    pub fn clear(&self) -> Result<(), String> {
+        let conn = self.conn.lock()
+            .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+        conn.execute_batch(
+            r#"
+            DELETE FROM events;
+            DELETE FROM daily_totals;
+            DELETE FROM daily_techniques;
+            DELETE FROM daily_severity;
+            DELETE FROM daily_hours;
+            DELETE FROM daily_profiles;
+            "#,
+        )
+        .map_err(|e| format!("Failed to clear analytics: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(source: EventSource, techniques: &[&str], severity: i32, latency_ms: u64) -> NeutralizationEvent {
+        NeutralizationEvent {
+            timestamp: now(),
+            platform: "twitter".to_string(),
+            techniques: techniques.iter().map(|t| t.to_string()).collect(),
+            severity,
+            latency_ms,
+            model: "phi3:mini".to_string(),
+            source,
+            profile: "kid".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_summary_aggregates_rollups() {
+        let analytics = Analytics::in_memory().unwrap();
+        analytics.record(&event(EventSource::Model, &["fear_appeal", "all_caps"], 8, 1200)).unwrap();
+        analytics.record(&event(EventSource::Model, &["fear_appeal"], 4, 800)).unwrap();
+        analytics.record(&event(EventSource::Cache, &["fear_appeal"], 4, 2)).unwrap();
+        analytics.record(&NeutralizationEvent {
+            timestamp: now() - 400 * 24 * 60 * 60,
+            ..event(EventSource::Model, &["old"], 9, 5000)
+        }).unwrap();
+
+        let summary = analytics.summary(Some(7)).unwrap();
+        let trend = analytics.daily_trend(7).unwrap();
+        assert_eq!((trend.len(), trend[0].events, trend[0].average_severity), (1, 3, 16.0 / 3.0));
+        assert_eq!(summary.total_events, 3);
+        assert_eq!(summary.technique_totals[0].name, "fear_appeal");
+        assert_eq!(summary.technique_totals[0].events, 3);
+        assert!(summary.technique_totals.iter().all(|t| t.name != "old"));
+        assert_eq!(summary.severity_distribution.len(), 2);
+        assert_eq!(summary.busiest_hours[0].total_severity, 16);
+        assert_eq!(summary.busiest_weekdays[0].events, 3);
+        assert_eq!(summary.average_inference_latency_ms, 1000.0);
+        assert_eq!(summary.by_platform[0].events, 3);
+        assert_eq!((summary.by_profile[0].name.as_str(), summary.by_profile[0].events), ("kid", 3));
+
+        // Old raw events go, rollups stay
+        assert_eq!(analytics.prune().unwrap(), 1);
+        assert_eq!(analytics.summary(Some(1000)).unwrap().total_events, 4);
+
+        analytics.clear().unwrap();
+        assert_eq!(analytics.summary(None).unwrap().total_events, 0);
+    }
+}
@@ -1,35 +1,1045 @@
-use rusqlite::{Connection, params};
+use crate::actions::ActionDecision;
+use crate::crypto::{self, DataKey, Keyfile};
+use rusqlite::{Connection, params, params_from_iter};
+use rusqlite::types::Value;
 use serde::{Deserialize, Serialize};
 use sha2::{Sha256, Digest};
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use unicode_normalization::UnicodeNormalization;
 
-const CACHE_TTL_HOURS: i64 = 24;
-const MAX_CACHE_ENTRIES: i64 = 50000;
+/// Entries kept in the in-memory hot tier
+const HOT_CACHE_SIZE: usize = 512;
+/// History entries per page unless the query asks otherwise
+const HISTORY_PAGE_SIZE: usize = 50;
+/// Largest history page a query can ask for
+const HISTORY_MAX_PAGE_SIZE: usize = 200;
+/// Lookups between writes of batched stats to SQLite
+const STATS_FLUSH_EVERY: u32 = 64;
+/// Longest batched stats wait before the background flush writes them
+pub const STATS_FLUSH_INTERVAL: Duration = Duration::from_secs(30);
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CachedNeutralization {
     pub content_hash: String,
+    /// Empty for entries stored in privacy mode
     pub original: String,
     pub neutralized: String,
     pub techniques: Vec<String>,
     pub severity: i32,
     pub created_at: i64,
     pub hit_count: i64,
+    pub model: String,
+    pub prompt_version: String,
+    pub persona: String,
+    /// Where the content was found
+    #[serde(default)]
+    pub source: ContentSource,
+    /// 1.0 for an exact cache hit, the estimated similarity for a
+    /// near-duplicate hit, absent for a fresh result
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub similarity: Option<f64>,
+    /// What to do with the content under the policy in force; set per
+    /// request, absent from history and exports
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action: Option<ActionDecision>,
+}
+
+/// What kind of element on the page the content came from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentKind {
+    #[default]
+    Unknown,
+    Post,
+    Comment,
+    Title,
+}
+
+impl ContentKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ContentKind::Unknown => "unknown",
+            ContentKind::Post => "post",
+            ContentKind::Comment => "comment",
+            ContentKind::Title => "title",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "post" => ContentKind::Post,
+            "comment" => ContentKind::Comment,
+            "title" => ContentKind::Title,
+            _ => ContentKind::Unknown,
+        }
+    }
+}
+
+/// Where a piece of content was found. Not part of the cache key: the same
+/// text gets the same result wherever it appears.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContentSource {
+    /// Site, e.g. "twitter", "facebook" or "youtube"; empty if unknown
+    pub platform: String,
+    pub kind: ContentKind,
+    /// SHA-256 of the author's handle, never the handle itself
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author_hash: Option<String>,
+}
+
+impl ContentSource {
+    /// Lowercase the platform and make sure the author is only kept hashed.
+    /// A value that isn't already a SHA-256 hex digest is treated as a raw
+    /// handle and hashed here.
+    pub fn normalized(mut self) -> Self {
+        self.platform = self.platform.trim().to_lowercase();
+        self.author_hash = self.author_hash.and_then(|author| {
+            let author = author.trim();
+            if author.is_empty() {
+                return None;
+            }
+            if author.len() == 64 && author.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return Some(author.to_ascii_lowercase());
+            }
+            let handle = author.trim_start_matches('@').to_lowercase();
+            Some(hex::encode(Sha256::digest(handle.as_bytes())))
+        });
+        self
+    }
+}
+
+/// Everything besides the content that changes a neutralization result.
+/// Two lookups only share a cache entry when all of these match.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CacheKey {
+    pub model: String,
+    pub prompt_version: String,
+    pub persona: String,
+}
+
+impl CacheKey {
+    /// Composite key for a piece of content under this model/prompt/persona
+    pub fn composite(&self, content_hash: &str) -> String {
+        let mut hasher = Sha256::new();
+        for part in [content_hash, &self.model, &self.prompt_version, &self.persona] {
+            hasher.update(part.as_bytes());
+            hasher.update([0u8]);
+        }
+        hex::encode(hasher.finalize())
+    }
+}
+
+/// Which entries go first once the cache is over its limits
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EvictionPolicy {
+    /// Least recently used
+    Lru,
+    /// Least frequently used, oldest access breaking ties
+    Lfu,
+}
+
+/// Retention limits for the cache (stored in `AppSettings`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CachePolicy {
+    /// Hours before an entry expires
+    pub ttl_hours: u32,
+    /// Entries kept on disk
+    pub max_entries: u32,
+    /// Database size limit, counting only pages in use
+    pub max_bytes: u64,
+    /// How entries are chosen once a limit is exceeded
+    pub eviction: EvictionPolicy,
+    /// Seconds between background prunes
+    pub prune_interval_secs: u64,
+    /// Keep only hashes and results; originals are never written to disk
+    pub privacy_mode: bool,
+}
+
+impl Default for CachePolicy {
+    fn default() -> Self {
+        Self {
+            ttl_hours: 24,
+            max_entries: 50000,
+            max_bytes: 256 * 1024 * 1024,
+            eviction: EvictionPolicy::Lfu,
+            prune_interval_secs: 600,
+            privacy_mode: false,
+        }
+    }
+}
+
+impl CachePolicy {
+    /// Entries created at or before this time have expired
+    fn expiry_cutoff(&self, now: i64) -> i64 {
+        now - i64::from(self.ttl_hours) * 3600
+    }
+
+    fn eviction_order(&self) -> &'static str {
+        match self.eviction {
+            EvictionPolicy::Lru => "last_accessed ASC",
+            EvictionPolicy::Lfu => "hit_count ASC, last_accessed ASC",
+        }
+    }
+}
+
+/// Disk usage of the cache
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheUsage {
+    pub entries: i64,
+    /// Database plus write-ahead log on disk
+    pub file_bytes: u64,
+    /// Bytes in pages holding data; what `max_bytes` is checked against
+    pub used_bytes: u64,
+    pub max_entries: u32,
+    pub max_bytes: u64,
+}
+
+/// Whether cache contents are encrypted, and whether the key is loaded
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptionStatus {
+    pub enabled: bool,
+    pub unlocked: bool,
+}
+
+/// Encryption of the `original` and `neutralized` columns
+enum CipherState {
+    Disabled,
+    /// Encrypted, but the passphrase hasn't been entered this session. The
+    /// cache is bypassed rather than written in plaintext.
+    Locked,
+    Unlocked(DataKey),
+}
+
+/// Identifies a cache export file; bump `EXPORT_VERSION` on format changes
+const EXPORT_FORMAT: &str = "feelingwise-cache";
+const EXPORT_VERSION: u32 = 1;
+
+/// First line of an export file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ExportHeader {
+    format: String,
+    version: u32,
+    exported_at: i64,
+    entries: usize,
+}
+
+/// One exported entry. Originals are never exported; the content hash and
+/// similarity signature are enough to match lookups on another device.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ExportedEntry {
+    content_hash: String,
+    model: String,
+    prompt_version: String,
+    persona: String,
+    neutralized: String,
+    techniques: Vec<String>,
+    severity: i32,
+    created_at: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    signature: Option<String>,
+}
+
+/// Outcome of an import
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportReport {
+    /// New entries, or entries newer than the local copy
+    pub imported: usize,
+    /// Conflicts where the local entry was newer
+    pub kept_local: usize,
+    /// Entries made with a different prompt template
+    pub skipped_incompatible: usize,
+    /// Entries already past the TTL
+    pub skipped_expired: usize,
+    /// Lines that could not be parsed, or entries dated in the future
+    pub skipped_invalid: usize,
+}
+
+/// Filters and page for a history search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HistoryQuery {
+    /// Words matched against originals and outputs; the last word of the
+    /// query also matches as a prefix
+    pub text: Option<String>,
+    pub technique: Option<String>,
+    pub min_severity: Option<i32>,
+    pub max_severity: Option<i32>,
+    /// Unix seconds, inclusive
+    pub from: Option<i64>,
+    /// Unix seconds, exclusive
+    pub to: Option<i64>,
+    pub platform: Option<String>,
+    pub kind: Option<ContentKind>,
+    pub author_hash: Option<String>,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+impl Default for HistoryQuery {
+    fn default() -> Self {
+        Self {
+            text: None,
+            technique: None,
+            min_severity: None,
+            max_severity: None,
+            from: None,
+            to: None,
+            platform: None,
+            kind: None,
+            author_hash: None,
+            offset: 0,
+            limit: HISTORY_PAGE_SIZE,
+        }
+    }
+}
+
+/// One page of history, newest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryPage {
+    pub items: Vec<CachedNeutralization>,
+    /// Matching entries across all pages
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+/// Selects cache entries to drop
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "by", content = "value", rename_all = "snake_case")]
+pub enum CacheInvalidation {
+    /// Every cached result for this text
+    Content(String),
+    /// Results produced by this model tag
+    Model(String),
+    /// Results produced with this prompt-template version
+    PromptVersion(String),
+    /// Results produced for this persona
+    Persona(String),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CacheStats {
     pub total_entries: i64,
+    /// Exact plus near-duplicate hits
     pub cache_hits: i64,
+    pub exact_hits: i64,
+    pub fuzzy_hits: i64,
     pub cache_misses: i64,
     pub hit_rate: f64,
+    /// Entries currently held in the in-memory hot tier
+    pub hot_entries: usize,
+}
+
+// ============================================================================
+// CONTENT NORMALIZATION
+// ============================================================================
+
+/// Invisible characters that platforms and copy-paste sprinkle into text
+const INVISIBLE_CHARS: &[char] = &['\u{200B}', '\u{200C}', '\u{200D}', '\u{2060}', '\u{FEFF}', '\u{00AD}'];
+
+/// Truncation and UI text platforms append to post bodies
+const BOILERPLATE_SUFFIXES: &[&str] = &[
+    "show more",
+    "see more",
+    "show less",
+    "see less",
+    "read more",
+    "more",
+    "see translation",
+    "translate post",
+    "translate tweet",
+];
+
+/// Query parameters that only track the share, never change the target
+const TRACKING_PARAMS: &[&str] = &[
+    "fbclid", "gclid", "igshid", "mibextid", "si", "feature", "ref_src", "ref_url", "__cft__",
+    "__tn__", "rdid", "share_url", "sfnsn", "pp",
+];
+
+/// Canonical form of post text used for cache hashing.
+///
+/// Runs NFKC, drops invisible characters, canonicalizes URLs, strips
+/// trailing platform boilerplate and collapses whitespace, so the same post
+/// copied from different places hashes the same.
+pub fn normalize_content(content: &str) -> String {
+    let text: String = content
+        .nfkc()
+        .filter(|c| !INVISIBLE_CHARS.contains(c))
+        .collect();
+
+    let words: Vec<String> = text
+        .split_whitespace()
+        .map(|word| {
+            if word.starts_with("http://") || word.starts_with("https://") {
+                canonicalize_url(word)
+            } else {
+                word.to_string()
+            }
+        })
+        .collect();
+
+    strip_boilerplate(&words.join(" "))
+}
+
+/// Remove trailing "… See more" style text, repeatedly
+fn strip_boilerplate(text: &str) -> String {
+    let mut text = text.trim_end().to_string();
+    loop {
+        let lower = text.to_lowercase();
+        let Some(suffix) = BOILERPLATE_SUFFIXES.iter().find(|s| {
+            lower.ends_with(*s) && {
+                // Only whole words, so "nevermore" keeps its "more"
+                let before = &lower[..lower.len() - s.len()];
+                before.is_empty() || before.ends_with([' ', '.', '…'])
+            }
+        }) else {
+            break;
+        };
+
+        let mut stripped = text[..text.len() - suffix.len()].trim_end();
+        // A lone "more" is only boilerplate right after an ellipsis
+        if *suffix == "more" && !stripped.ends_with(['.', '…']) {
+            break;
+        }
+        stripped = stripped.trim_end_matches(['.', '…', ' ']);
+        text = stripped.to_string();
+    }
+    text
+}
+
+/// Canonical form of a URL: lowercase host without `www.`/`m.`, platform
+/// aliases unified, tracking parameters and fragments dropped
+fn canonicalize_url(url: &str) -> String {
+    let url = url.trim_end_matches([',', '.', ')', '!', '?']);
+    let Some((_, rest)) = url.split_once("://") else {
+        return url.to_string();
+    };
+
+    let rest = rest.split('#').next().unwrap_or_default();
+    let (location, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let (host, path) = location
+        .find('/')
+        .map(|i| location.split_at(i))
+        .unwrap_or((location, ""));
+
+    let mut host = host.to_lowercase();
+    for prefix in ["www.", "m.", "mobile.", "mbasic."] {
+        if let Some(stripped) = host.strip_prefix(prefix) {
+            host = stripped.to_string();
+        }
+    }
+    if host == "x.com" {
+        host = "twitter.com".to_string();
+    }
+
+    let mut path = path.trim_end_matches('/').to_string();
+    let mut params: Vec<&str> = query
+        .split('&')
+        .filter(|p| !p.is_empty())
+        .filter(|p| {
+            let name = p.split('=').next().unwrap_or_default();
+            let twitter_share = host == "twitter.com" && (name == "s" || name == "t");
+            !name.starts_with("utm_") && !TRACKING_PARAMS.contains(&name) && !twitter_share
+        })
+        .collect();
+
+    // youtu.be/<id> is the same video as youtube.com/watch?v=<id>
+    let short_id;
+    if host == "youtu.be" {
+        short_id = format!("v={}", path.trim_start_matches('/'));
+        host = "youtube.com".to_string();
+        path = "/watch".to_string();
+        params.push(&short_id);
+    }
+
+    params.sort_unstable();
+    if params.is_empty() {
+        format!("https://{}{}", host, path)
+    } else {
+        format!("https://{}{}?{}", host, path, params.join("&"))
+    }
+}
+
+// ============================================================================
+// SIMILARITY INDEX
+// ============================================================================
+
+/// Number of MinHash values per signature
+const MINHASH_SIZE: usize = 64;
+/// Rows per LSH band; `MINHASH_SIZE / MINHASH_BAND_ROWS` bands are indexed
+const MINHASH_BAND_ROWS: usize = 4;
+/// Lowest estimated similarity served as a near-duplicate hit. Kept high
+/// because a single added "not" is also a small edit.
+const FUZZY_MIN_SIMILARITY: f64 = 0.9;
+/// Posts with fewer shingles than this are too short to match fuzzily
+const FUZZY_MIN_SHINGLES: usize = 8;
+
+/// Word unigrams and bigrams of the normalized content
+fn shingles(content: &str) -> HashSet<String> {
+    let words: Vec<String> = normalize_content(content)
+        .split_whitespace()
+        .map(|w| {
+            w.trim_matches(|c: char| c.is_ascii_punctuation())
+                .to_lowercase()
+        })
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    let mut shingles: HashSet<String> = words.iter().cloned().collect();
+    for pair in words.windows(2) {
+        shingles.insert(format!("{} {}", pair[0], pair[1]));
+    }
+    shingles
+}
+
+/// FNV-1a, stable across builds unlike `DefaultHasher`
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ u64::from(*b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// splitmix64 finalizer, used to derive independent hash functions
+fn mix(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+/// MinHash signature, or `None` when the content is too short to compare
+fn minhash(content: &str) -> Option<Vec<u64>> {
+    let shingles = shingles(content);
+    if shingles.len() < FUZZY_MIN_SHINGLES {
+        return None;
+    }
+
+    let hashes: Vec<u64> = shingles.iter().map(|s| fnv1a(s.as_bytes())).collect();
+    let signature = (0..MINHASH_SIZE as u64)
+        .map(|i| {
+            let seed = i.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+            hashes.iter().map(|h| mix(h ^ seed)).min().unwrap_or(u64::MAX)
+        })
+        .collect();
+    Some(signature)
+}
+
+/// Estimated Jaccard similarity of two signatures
+fn signature_similarity(a: &[u64], b: &[u64]) -> f64 {
+    let equal = a.iter().zip(b).filter(|(x, y)| x == y).count();
+    equal as f64 / MINHASH_SIZE as f64
+}
+
+/// LSH band keys; near-duplicates share at least one with high probability
+fn band_keys(signature: &[u64]) -> Vec<i64> {
+    signature
+        .chunks(MINHASH_BAND_ROWS)
+        .enumerate()
+        .map(|(band, rows)| {
+            let mut bytes = (band as u64).to_le_bytes().to_vec();
+            for row in rows {
+                bytes.extend_from_slice(&row.to_le_bytes());
+            }
+            fnv1a(&bytes) as i64
+        })
+        .collect()
+}
+
+fn signature_to_blob(signature: &[u64]) -> Vec<u8> {
+    signature.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn signature_from_blob(blob: &[u8]) -> Vec<u64> {
+    blob.chunks_exact(8)
+        .map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap_or_default()))
+        .collect()
+}
+
+// ============================================================================
+// SCHEMA MIGRATIONS
+// ============================================================================
+
+type Migration = fn(&Connection) -> rusqlite::Result<()>;
+
+/// Ordered schema migrations; entry `i` upgrades `user_version` `i` to
+/// `i + 1`. Released migrations are never edited, only appended to.
+const MIGRATIONS: &[Migration] = &[
+    migrate_v1_composite_key,
+    migrate_v2_similarity_index,
+    migrate_v3_last_accessed,
+    migrate_v4_history,
+    migrate_v5_content_source,
+    migrate_v6_history_secure_delete,
+];
+
+/// Schema version of a fully migrated database
+const SCHEMA_VERSION: i64 = MIGRATIONS.len() as i64;
+
+/// Column names of a table; empty if the table does not exist
+fn table_columns(conn: &Connection, table: &str) -> rusqlite::Result<Vec<String>> {
+    conn.prepare(&format!("SELECT name FROM pragma_table_info('{}')", table))?
+        .query_map([], |row| row.get(0))?
+        .collect()
+}
+
+/// v1: entries keyed by content, model, prompt version and persona.
+///
+/// Unversioned databases hold either nothing or the original table keyed by
+/// `content_hash` alone. Legacy rows keep their data but get empty
+/// model/prompt/persona columns, so they never match a new lookup and age
+/// out with the TTL.
+fn migrate_v1_composite_key(conn: &Connection) -> rusqlite::Result<()> {
+    let columns = table_columns(conn, "neutralization_cache")?;
+    let legacy = !columns.is_empty() && !columns.iter().any(|c| c == "cache_key");
+
+    if legacy {
+        conn.execute_batch(
+            r#"
+            ALTER TABLE neutralization_cache RENAME TO neutralization_cache_legacy;
+            DROP INDEX IF EXISTS idx_created_at;
+            DROP INDEX IF EXISTS idx_hit_count;
+            "#,
+        )?;
+    }
+
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS neutralization_cache (
+            cache_key TEXT PRIMARY KEY,
+            content_hash TEXT NOT NULL,
+            model TEXT NOT NULL DEFAULT '',
+            prompt_version TEXT NOT NULL DEFAULT '',
+            persona TEXT NOT NULL DEFAULT '',
+            original TEXT NOT NULL,
+            neutralized TEXT NOT NULL,
+            techniques TEXT NOT NULL,
+            severity INTEGER NOT NULL,
+            created_at INTEGER NOT NULL,
+            hit_count INTEGER DEFAULT 0
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_created_at ON neutralization_cache(created_at);
+        CREATE INDEX IF NOT EXISTS idx_hit_count ON neutralization_cache(hit_count);
+        CREATE INDEX IF NOT EXISTS idx_content_hash ON neutralization_cache(content_hash);
+        CREATE INDEX IF NOT EXISTS idx_model ON neutralization_cache(model);
+        CREATE INDEX IF NOT EXISTS idx_prompt_version ON neutralization_cache(prompt_version);
+        CREATE INDEX IF NOT EXISTS idx_persona ON neutralization_cache(persona);
+
+        CREATE TABLE IF NOT EXISTS cache_stats (
+            id INTEGER PRIMARY KEY CHECK (id = 1),
+            total_hits INTEGER DEFAULT 0,
+            total_misses INTEGER DEFAULT 0
+        );
+
+        INSERT OR IGNORE INTO cache_stats (id, total_hits, total_misses) VALUES (1, 0, 0);
+        "#,
+    )?;
+
+    if legacy {
+        conn.execute_batch(
+            r#"
+            INSERT INTO neutralization_cache
+                (cache_key, content_hash, original, neutralized, techniques, severity, created_at, hit_count)
+            SELECT content_hash, content_hash, original, neutralized, techniques, severity, created_at, hit_count
+            FROM neutralization_cache_legacy;
+            DROP TABLE neutralization_cache_legacy;
+            "#,
+        )?;
+    }
+    Ok(())
+}
+
+/// v2: MinHash index for near-duplicate lookups, and fuzzy hit stats
+fn migrate_v2_similarity_index(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS similarity_index (
+            cache_key TEXT PRIMARY KEY,
+            signature BLOB NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS similarity_bands (
+            band INTEGER NOT NULL,
+            cache_key TEXT NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_similarity_band ON similarity_bands(band);
+        CREATE INDEX IF NOT EXISTS idx_similarity_band_key ON similarity_bands(cache_key);
+
+        CREATE TRIGGER IF NOT EXISTS trg_similarity_cleanup
+        AFTER DELETE ON neutralization_cache
+        BEGIN
+            DELETE FROM similarity_index WHERE cache_key = OLD.cache_key;
+            DELETE FROM similarity_bands WHERE cache_key = OLD.cache_key;
+        END;
+        "#,
+    )?;
+
+    if !table_columns(conn, "cache_stats")?.iter().any(|c| c == "fuzzy_hits") {
+        conn.execute("ALTER TABLE cache_stats ADD COLUMN fuzzy_hits INTEGER DEFAULT 0", [])?;
+    }
+
+    // Index entries cached before the similarity index existed
+    let missing: Vec<(String, String)> = conn
+        .prepare(
+            "SELECT cache_key, original FROM neutralization_cache
+             WHERE cache_key NOT IN (SELECT cache_key FROM similarity_index)",
+        )?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<_, _>>()?;
+
+    for (cache_key, original) in missing {
+        index_similarity(conn, &cache_key, &original)?;
+    }
+    Ok(())
+}
+
+/// v3: last access time, for LRU eviction
+fn migrate_v3_last_accessed(conn: &Connection) -> rusqlite::Result<()> {
+    if !table_columns(conn, "neutralization_cache")?.iter().any(|c| c == "last_accessed") {
+        conn.execute_batch(
+            r#"
+            ALTER TABLE neutralization_cache ADD COLUMN last_accessed INTEGER NOT NULL DEFAULT 0;
+            UPDATE neutralization_cache SET last_accessed = created_at;
+            "#,
+        )?;
+    }
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_last_accessed ON neutralization_cache(last_accessed)",
+        [],
+    )?;
+    Ok(())
+}
+
+/// v4: source platform and a full-text index over originals and outputs.
+///
+/// The index keeps its own copy of the text keyed by the entry's rowid.
+/// Encrypted values are indexed as empty, so ciphertext never becomes
+/// searchable; history search over an encrypted cache matches in memory.
+fn migrate_v4_history(conn: &Connection) -> rusqlite::Result<()> {
+    if !table_columns(conn, "neutralization_cache")?.iter().any(|c| c == "platform") {
+        conn.execute(
+            "ALTER TABLE neutralization_cache ADD COLUMN platform TEXT NOT NULL DEFAULT ''",
+            [],
+        )?;
+    }
+
+    conn.execute_batch(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_platform ON neutralization_cache(platform);
+        CREATE INDEX IF NOT EXISTS idx_severity ON neutralization_cache(severity);
+
+        CREATE VIRTUAL TABLE IF NOT EXISTS history_fts USING fts5(original, neutralized);
+
+        CREATE TRIGGER IF NOT EXISTS trg_history_insert
+        AFTER INSERT ON neutralization_cache
+        BEGIN
+            INSERT INTO history_fts (rowid, original, neutralized) VALUES (
+                NEW.rowid,
+                CASE WHEN NEW.original LIKE 'enc1:%' THEN '' ELSE NEW.original END,
+                CASE WHEN NEW.neutralized LIKE 'enc1:%' THEN '' ELSE NEW.neutralized END
+            );
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS trg_history_delete
+        AFTER DELETE ON neutralization_cache
+        BEGIN
+            DELETE FROM history_fts WHERE rowid = OLD.rowid;
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS trg_history_update
+        AFTER UPDATE OF original, neutralized ON neutralization_cache
+        BEGIN
+            DELETE FROM history_fts WHERE rowid = OLD.rowid;
+            INSERT INTO history_fts (rowid, original, neutralized) VALUES (
+                NEW.rowid,
+                CASE WHEN NEW.original LIKE 'enc1:%' THEN '' ELSE NEW.original END,
+                CASE WHEN NEW.neutralized LIKE 'enc1:%' THEN '' ELSE NEW.neutralized END
+            );
+        END;
+        "#,
+    )?;
+
+    rebuild_history_index(conn)
+}
+
+/// v5: content kind and hashed author alongside the platform
+fn migrate_v5_content_source(conn: &Connection) -> rusqlite::Result<()> {
+    let columns = table_columns(conn, "neutralization_cache")?;
+    if !columns.iter().any(|c| c == "content_kind") {
+        conn.execute(
+            "ALTER TABLE neutralization_cache ADD COLUMN content_kind TEXT NOT NULL DEFAULT 'unknown'",
+            [],
+        )?;
+    }
+    if !columns.iter().any(|c| c == "author_hash") {
+        conn.execute("ALTER TABLE neutralization_cache ADD COLUMN author_hash TEXT", [])?;
+    }
+    conn.execute_batch(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_content_kind ON neutralization_cache(content_kind);
+        CREATE INDEX IF NOT EXISTS idx_author_hash ON neutralization_cache(author_hash);
+        "#,
+    )
+}
+
+/// v6: history index that erases removed entries instead of leaving their
+/// words behind in FTS5 segments
+fn migrate_v6_history_secure_delete(conn: &Connection) -> rusqlite::Result<()> {
+    rebuild_history_index(conn)
+}
+
+/// Re-index every entry. Needed after `VACUUM`, which may renumber rowids.
+///
+/// The table is recreated rather than emptied: FTS5 deletes only add
+/// tombstones, so removed text would otherwise stay in `history_fts_data`.
+fn rebuild_history_index(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        DROP TABLE IF EXISTS history_fts;
+        CREATE VIRTUAL TABLE history_fts USING fts5(original, neutralized);
+        INSERT INTO history_fts (history_fts, rank) VALUES ('secure-delete', 1);
+        INSERT INTO history_fts (rowid, original, neutralized)
+        SELECT rowid,
+               CASE WHEN original LIKE 'enc1:%' THEN '' ELSE original END,
+               CASE WHEN neutralized LIKE 'enc1:%' THEN '' ELSE neutralized END
+        FROM neutralization_cache;
+        "#,
+    )
+}
+
+/// FTS5 query matching every word, the last one also as a prefix. Words are
+/// quoted so user input can't inject query syntax.
+fn fts_query(words: &[String]) -> String {
+    let last = words.len().saturating_sub(1);
+    words
+        .iter()
+        .enumerate()
+        .map(|(i, word)| {
+            let quoted = format!("\"{}\"", word.replace('"', "\"\""));
+            if i == last { quoted + "*" } else { quoted }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Keyfile holding the wrapped data key, next to the database
+fn keyfile_path(db_path: &Path) -> PathBuf {
+    db_path.with_extension("key")
+}
+
+/// Re-encrypt the sensitive columns of every entry from one key to another
+/// (`None` meaning plaintext). Entries the old key can't open are dropped.
+fn reencrypt_all(conn: &mut Connection, from: Option<&DataKey>, to: Option<&DataKey>) -> Result<(), String> {
+    let transform = |value: &str| -> Result<String, String> {
+        // Privacy-mode originals stay empty
+        if value.is_empty() {
+            return Ok(String::new());
+        }
+        let plain = match from {
+            Some(key) => crypto::decrypt_field(key, value)?,
+            None if crypto::is_encrypted(value) => return Err("Entry is already encrypted".to_string()),
+            None => value.to_string(),
+        };
+        match to {
+            Some(key) => crypto::encrypt_field(key, &plain),
+            None => Ok(plain),
+        }
+    };
+
+    let tx = conn.transaction()
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+    let rows: Vec<(String, String, String)> = tx
+        .prepare("SELECT cache_key, original, neutralized FROM neutralization_cache")
+        .and_then(|mut stmt| {
+            stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+                .collect::<Result<_, _>>()
+        })
+        .map_err(|e| format!("Failed to read cache entries: {}", e))?;
+
+    for (cache_key, original, neutralized) in rows {
+        let result = match (transform(&original), transform(&neutralized)) {
+            (Ok(original), Ok(neutralized)) => tx.execute(
+                "UPDATE neutralization_cache SET original = ?1, neutralized = ?2 WHERE cache_key = ?3",
+                params![original, neutralized, cache_key],
+            ),
+            _ => tx.execute(
+                "DELETE FROM neutralization_cache WHERE cache_key = ?1",
+                params![cache_key],
+            ),
+        };
+        result.map_err(|e| format!("Failed to re-encrypt cache entry: {}", e))?;
+    }
+
+    tx.commit().map_err(|e| format!("Failed to commit re-encryption: {}", e))
+}
+
+/// Rewrite the file so old page contents don't survive in free space or the
+/// WAL. The history index is rebuilt before `VACUUM` so none of it holds
+/// removed text, and again after since rowids may have moved.
+fn purge_free_space(conn: &Connection) -> Result<(), String> {
+    rebuild_history_index(conn)
+        .and_then(|_| conn.execute_batch("VACUUM"))
+        .and_then(|_| rebuild_history_index(conn))
+        .and_then(|_| conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(())))
+        .map_err(|e| format!("Failed to compact cache: {}", e))
+}
+
+/// Bytes in database pages that hold data
+fn used_bytes(conn: &Connection) -> rusqlite::Result<u64> {
+    let pragma = |name: &str| conn.query_row(&format!("PRAGMA {}", name), [], |row| row.get::<_, i64>(0));
+    let used_pages = pragma("page_count")? - pragma("freelist_count")?;
+    Ok((used_pages.max(0) * pragma("page_size")?) as u64)
+}
+
+/// Store the MinHash signature and LSH bands for an entry. Short content
+/// gets an empty signature so it is not revisited by the backfill.
+fn index_similarity(conn: &Connection, cache_key: &str, original: &str) -> rusqlite::Result<()> {
+    index_signature(conn, cache_key, &minhash(original).unwrap_or_default())
+}
+
+fn index_signature(conn: &Connection, cache_key: &str, signature: &[u64]) -> rusqlite::Result<()> {
+    conn.execute(
+        "DELETE FROM similarity_bands WHERE cache_key = ?1",
+        params![cache_key],
+    )?;
+    conn.execute(
+        "INSERT OR REPLACE INTO similarity_index (cache_key, signature) VALUES (?1, ?2)",
+        params![cache_key, signature_to_blob(signature)],
+    )?;
+
+    for band in band_keys(signature) {
+        conn.execute(
+            "INSERT INTO similarity_bands (band, cache_key) VALUES (?1, ?2)",
+            params![band, cache_key],
+        )?;
+    }
+    Ok(())
+}
+
+/// Bring a database up to `SCHEMA_VERSION`, one transaction per migration.
+/// An existing file is copied to `<db>.v<N>.bak` first.
+fn migrate(conn: &mut Connection, path: Option<&Path>) -> rusqlite::Result<()> {
+    let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version > SCHEMA_VERSION {
+        return Err(unusable(format!(
+            "schema version {} is newer than supported version {}",
+            version, SCHEMA_VERSION
+        )));
+    }
+    if version == SCHEMA_VERSION {
+        return Ok(());
+    }
+
+    let tables: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table'",
+        [],
+        |row| row.get(0),
+    )?;
+    if let (Some(path), true) = (path, tables > 0) {
+        let backup = backup_path(path, version);
+        let _ = std::fs::remove_file(&backup);
+        conn.execute("VACUUM INTO ?1", params![backup.to_string_lossy()])?;
+        log::info!("Backed up cache database to {:?}", backup);
+    }
+
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        let target = index as i64 + 1;
+        let tx = conn.transaction()?;
+        migration(&tx)?;
+        tx.pragma_update(None, "user_version", target)?;
+        tx.commit()?;
+        log::info!("Migrated cache schema to version {}", target);
+    }
+    Ok(())
+}
+
+/// Settings every cache connection needs
+fn configure(conn: &Connection) -> rusqlite::Result<()> {
+    // `INSERT OR REPLACE` only fires delete triggers with this on, and the
+    // similarity and history indexes rely on them
+    conn.pragma_update(None, "recursive_triggers", true)
+}
+
+/// Open a database file, verify it and migrate it
+fn open_database(path: &Path) -> rusqlite::Result<Connection> {
+    let mut conn = Connection::open(path)?;
+    configure(&conn)?;
+
+    // WAL lets lookups read while a write is in progress
+    let _: String = conn.query_row("PRAGMA journal_mode = WAL", [], |row| row.get(0))?;
+    conn.pragma_update(None, "synchronous", "NORMAL")?;
+    // Overwrite deleted entries instead of leaving them in free pages
+    conn.pragma_update(None, "secure_delete", true)?;
+
+    let check: String = conn.query_row("PRAGMA quick_check", [], |row| row.get(0))?;
+    if check != "ok" {
+        return Err(unusable(check));
+    }
+
+    migrate(&mut conn, Some(path))?;
+    Ok(conn)
+}
+
+fn backup_path(path: &Path, version: i64) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".v{}.bak", version));
+    PathBuf::from(name)
+}
+
+fn unusable(message: String) -> rusqlite::Error {
+    rusqlite::Error::SqliteFailure(
+        rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_CORRUPT),
+        Some(message),
+    )
+}
+
+/// Whether an error means the file can't be used and should be rebuilt
+fn is_unusable(error: &rusqlite::Error) -> bool {
+    matches!(
+        error,
+        rusqlite::Error::SqliteFailure(e, _)
+            if matches!(e.code, rusqlite::ErrorCode::DatabaseCorrupt | rusqlite::ErrorCode::NotADatabase)
+    )
+}
+
+/// Columns read by `NeutralizationCache::entry_from_row`, in order
+const ENTRY_COLUMNS: &str = "content_hash, original, neutralized, techniques, severity, created_at, \
+                             hit_count, model, prompt_version, persona, platform, \
+                             content_kind, author_hash";
+
+/// Stats and hit counts not yet written to SQLite
+#[derive(Default)]
+struct PendingStats {
+    hits: i64,
+    fuzzy_hits: i64,
+    misses: i64,
+    /// Hits and latest access time per entry
+    hit_counts: HashMap<String, (i64, i64)>,
+    lookups: u32,
 }
 
+/// SQLite-backed neutralization cache with an in-memory hot tier.
+///
+/// All methods block on SQLite; async code goes through [`Self::run`], which
+/// moves the work onto the blocking thread pool. Clones share the same
+/// database and hot tier.
+#[derive(Clone)]
 pub struct NeutralizationCache {
     conn: Arc<Mutex<Connection>>,
+    /// Database file; `None` for an in-memory cache
+    path: Option<Arc<PathBuf>>,
+    policy: Arc<Mutex<CachePolicy>>,
+    cipher: Arc<Mutex<CipherState>>,
+    hot: Arc<Mutex<LruCache>>,
+    pending: Arc<Mutex<PendingStats>>,
     hits: Arc<Mutex<i64>>,
+    fuzzy_hits: Arc<Mutex<i64>>,
     misses: Arc<Mutex<i64>>,
 }
 
@@ -47,128 +1057,414 @@ impl NeutralizationCache {
                 .map_err(|e| format!("Failed to create cache directory: {}", e))?;
         }
 
-        let conn = Connection::open(&path)
-            .map_err(|e| format!("Failed to open cache database: {}", e))?;
-
-        // Initialize schema
-        conn.execute_batch(
-            r#"
-            CREATE TABLE IF NOT EXISTS neutralization_cache (
-                content_hash TEXT PRIMARY KEY,
-                original TEXT NOT NULL,
-                neutralized TEXT NOT NULL,
-                techniques TEXT NOT NULL,
-                severity INTEGER NOT NULL,
-                created_at INTEGER NOT NULL,
-                hit_count INTEGER DEFAULT 0
-            );
-
-            CREATE INDEX IF NOT EXISTS idx_created_at ON neutralization_cache(created_at);
-            CREATE INDEX IF NOT EXISTS idx_hit_count ON neutralization_cache(hit_count);
+        let conn = match open_database(&path) {
+            Ok(conn) => conn,
+            Err(e) if is_unusable(&e) => {
+                // It's only a cache: set the file aside and start over
+                log::warn!("Cache database is unusable ({}), rebuilding", e);
+                Self::set_aside(&path)?;
+                open_database(&path)
+                    .map_err(|e| format!("Failed to rebuild cache database: {}", e))?
+            }
+            Err(e) => return Err(format!("Failed to open cache database: {}", e)),
+        };
 
-            CREATE TABLE IF NOT EXISTS cache_stats (
-                id INTEGER PRIMARY KEY CHECK (id = 1),
-                total_hits INTEGER DEFAULT 0,
-                total_misses INTEGER DEFAULT 0
-            );
+        let mut cache = Self::from_connection(conn);
+        if keyfile_path(&path).exists() {
+            cache.cipher = Arc::new(Mutex::new(CipherState::Locked));
+        }
+        cache.path = Some(Arc::new(path));
+        Ok(cache)
+    }
 
-            INSERT OR IGNORE INTO cache_stats (id, total_hits, total_misses) VALUES (1, 0, 0);
-            "#
-        ).map_err(|e| format!("Failed to initialize cache schema: {}", e))?;
+    /// Cache that lives only for this run, used when the file can't be opened
+    pub fn in_memory() -> Result<Self, String> {
+        let mut conn = Connection::open_in_memory()
+            .map_err(|e| format!("Failed to open in-memory cache: {}", e))?;
+        configure(&conn)
+            .map_err(|e| format!("Failed to open in-memory cache: {}", e))?;
+        migrate(&mut conn, None)
+            .map_err(|e| format!("Failed to initialize cache schema: {}", e))?;
+        Ok(Self::from_connection(conn))
+    }
 
+    fn from_connection(conn: Connection) -> Self {
         // Load stats
-        let (hits, misses): (i64, i64) = conn.query_row(
-            "SELECT total_hits, total_misses FROM cache_stats WHERE id = 1",
+        let (hits, misses, fuzzy_hits): (i64, i64, i64) = conn.query_row(
+            "SELECT total_hits, total_misses, fuzzy_hits FROM cache_stats WHERE id = 1",
             [],
-            |row| Ok((row.get(0)?, row.get(1)?))
-        ).unwrap_or((0, 0));
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        ).unwrap_or((0, 0, 0));
 
-        Ok(Self {
+        Self {
             conn: Arc::new(Mutex::new(conn)),
+            path: None,
+            policy: Arc::new(Mutex::new(CachePolicy::default())),
+            cipher: Arc::new(Mutex::new(CipherState::Disabled)),
+            hot: Arc::new(Mutex::new(LruCache::new(HOT_CACHE_SIZE))),
+            pending: Arc::new(Mutex::new(PendingStats::default())),
             hits: Arc::new(Mutex::new(hits)),
+            fuzzy_hits: Arc::new(Mutex::new(fuzzy_hits)),
             misses: Arc::new(Mutex::new(misses)),
-        })
+        }
+    }
+
+    /// Run blocking cache work on the blocking thread pool
+    pub async fn run<T, F>(&self, work: F) -> Result<T, String>
+    where
+        F: FnOnce(&NeutralizationCache) -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let cache = self.clone();
+        tokio::task::spawn_blocking(move || work(&cache))
+            .await
+            .map_err(|e| format!("Cache worker failed: {}", e))
+    }
+
+    /// Periodically write batched stats so they survive a crash
+    pub fn start_stats_flush(&self) {
+        let cache = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(STATS_FLUSH_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Ok(Err(e)) = cache.run(|c| c.flush_stats()).await {
+                    log::warn!("Failed to flush cache stats: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Prune on the policy's interval
+    pub fn start_pruning(&self) {
+        let cache = self.clone();
+        tokio::spawn(async move {
+            loop {
+                match cache.run(|c| c.prune()).await {
+                    Ok(Ok(removed)) if removed > 0 => log::info!("Pruned {} cache entries", removed),
+                    Ok(Err(e)) => log::warn!("Failed to prune cache: {}", e),
+                    _ => {}
+                }
+                let interval = cache.policy().prune_interval_secs.max(60);
+                tokio::time::sleep(Duration::from_secs(interval)).await;
+            }
+        });
     }
 
-    /// Generate a hash for content
+    pub fn policy(&self) -> CachePolicy {
+        self.policy.lock().map(|p| p.clone()).unwrap_or_default()
+    }
+
+    /// Apply new limits; they take effect at the next prune. Turning on
+    /// privacy mode scrubs the originals already stored.
+    pub fn set_policy(&self, policy: CachePolicy) -> Result<(), String> {
+        let privacy_enabled = policy.privacy_mode;
+        let previous = {
+            let mut current = self.policy.lock()
+                .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+            std::mem::replace(&mut *current, policy)
+        };
+
+        if privacy_enabled && !previous.privacy_mode {
+            self.scrub_originals()?;
+        }
+        Ok(())
+    }
+
+    /// Blank the stored originals and purge them from free pages and backups
+    fn scrub_originals(&self) -> Result<(), String> {
+        let conn = self.conn.lock()
+            .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+        let scrubbed = conn
+            .execute(
+                "UPDATE neutralization_cache SET original = '', author_hash = NULL
+                 WHERE original != '' OR author_hash IS NOT NULL",
+                [],
+            )
+            .map_err(|e| format!("Failed to scrub cached originals: {}", e))?;
+        if scrubbed > 0 {
+            purge_free_space(&conn)?;
+            log::info!("Privacy mode: scrubbed {} cached originals", scrubbed);
+        }
+        drop(conn);
+
+        if let Ok(mut hot) = self.hot.lock() {
+            hot.clear();
+        }
+        self.wipe_plaintext_copies()
+    }
+
+    /// Move an unusable database (and its journal files) out of the way
+    fn set_aside(path: &Path) -> Result<(), String> {
+        let mut broken = path.as_os_str().to_owned();
+        broken.push(".broken");
+        std::fs::rename(path, &broken)
+            .map_err(|e| format!("Failed to move unusable cache aside: {}", e))?;
+
+        for suffix in ["-wal", "-shm", "-journal"] {
+            let mut journal = path.as_os_str().to_owned();
+            journal.push(suffix);
+            let _ = std::fs::remove_file(PathBuf::from(journal));
+        }
+        Ok(())
+    }
+
+    /// Generate a hash for content, after normalization
     pub fn hash_content(content: &str) -> String {
         let mut hasher = Sha256::new();
-        hasher.update(content.as_bytes());
+        hasher.update(normalize_content(content).as_bytes());
         hex::encode(hasher.finalize())
     }
 
-    /// Look up a cached neutralization
-    pub fn get(&self, original: &str) -> Option<CachedNeutralization> {
+    /// Look up a cached neutralization, falling back to a near-duplicate
+    pub fn get(&self, original: &str, key: &CacheKey) -> Option<CachedNeutralization> {
         let content_hash = Self::hash_content(original);
-        let conn = self.conn.lock().ok()?;
+        let cache_key = key.composite(&content_hash);
 
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .ok()?
             .as_secs() as i64;
 
-        let cutoff = now - (CACHE_TTL_HOURS * 3600);
+        let cutoff = self.policy().expiry_cutoff(now);
+
+        let hot = self.hot.lock().ok()?.get(&cache_key).cloned();
+        if let Some(mut cached) = hot.filter(|c| c.created_at > cutoff) {
+            cached.similarity = Some(1.0);
+            self.record_lookup(Some((&cache_key, false)));
+            return Some(cached);
+        }
+
+        let found = {
+            let conn = self.conn.lock().ok()?;
+            match Self::load_entry(&conn, &cache_key, cutoff) {
+                Ok(cached) => Some((cache_key, cached, 1.0)),
+                Err(_) => Self::find_similar(&conn, original, key, cutoff).and_then(
+                    |(similar_key, similarity)| {
+                        Self::load_entry(&conn, &similar_key, cutoff)
+                            .ok()
+                            .map(|cached| (similar_key, cached, similarity))
+                    },
+                ),
+            }
+        };
+        // Entries that can't be decrypted right now count as misses
+        let found = found.and_then(|(found_key, cached, similarity)| {
+            self.reveal(cached).map(|mut cached| {
+                // Privacy mode keeps no original, and a near duplicate's is
+                // someone else's post; either way hand back the caller's text
+                if cached.original.is_empty() || similarity < 1.0 {
+                    cached.original = original.to_string();
+                }
+                (found_key, cached, similarity)
+            })
+        });
+
+        let Some((found_key, mut cached, similarity)) = found else {
+            self.record_lookup(None);
+            return None;
+        };
+
+        let fuzzy = similarity < 1.0;
+        if fuzzy {
+            log::info!("Near-duplicate cache hit (similarity {:.2})", similarity);
+        } else if let Ok(mut hot) = self.hot.lock() {
+            hot.insert(found_key.clone(), cached.clone());
+        }
+        self.record_lookup(Some((&found_key, fuzzy)));
+
+        cached.similarity = Some(similarity);
+        Some(cached)
+    }
+
+    fn load_entry(
+        conn: &Connection,
+        cache_key: &str,
+        cutoff: i64,
+    ) -> rusqlite::Result<CachedNeutralization> {
+        conn.query_row(
+            &format!(
+                "SELECT {} FROM neutralization_cache WHERE cache_key = ?1 AND created_at > ?2",
+                ENTRY_COLUMNS
+            ),
+            params![cache_key, cutoff],
+            Self::entry_from_row,
+        )
+    }
+
+    /// Build an entry from a row selected with [`ENTRY_COLUMNS`]
+    fn entry_from_row(row: &rusqlite::Row) -> rusqlite::Result<CachedNeutralization> {
+        let techniques_json: String = row.get(3)?;
+        let techniques: Vec<String> = serde_json::from_str(&techniques_json).unwrap_or_default();
+
+        Ok(CachedNeutralization {
+            content_hash: row.get(0)?,
+            original: row.get(1)?,
+            neutralized: row.get(2)?,
+            techniques,
+            severity: row.get(4)?,
+            created_at: row.get(5)?,
+            hit_count: row.get(6)?,
+            model: row.get(7)?,
+            prompt_version: row.get(8)?,
+            persona: row.get(9)?,
+            source: ContentSource {
+                platform: row.get(10)?,
+                kind: ContentKind::parse(&row.get::<_, String>(11)?),
+                author_hash: row.get(12)?,
+            },
+            similarity: None,
+            action: None,
+        })
+    }
+
+    /// Most similar live entry under the same key, if similar enough
+    fn find_similar(
+        conn: &Connection,
+        original: &str,
+        key: &CacheKey,
+        cutoff: i64,
+    ) -> Option<(String, f64)> {
+        let signature = minhash(original)?;
+        let bands = band_keys(&signature);
 
-        match conn.query_row(
+        let placeholders = vec!["?"; bands.len()].join(", ");
+        let sql = format!(
             r#"
-            SELECT content_hash, original, neutralized, techniques, severity, created_at, hit_count
-            FROM neutralization_cache
-            WHERE content_hash = ?1 AND created_at > ?2
+            SELECT DISTINCT s.cache_key, s.signature
+            FROM similarity_bands b
+            JOIN similarity_index s ON s.cache_key = b.cache_key
+            JOIN neutralization_cache c ON c.cache_key = b.cache_key
+            WHERE b.band IN ({})
+              AND c.model = ? AND c.prompt_version = ? AND c.persona = ? AND c.created_at > ?
             "#,
-            params![content_hash, cutoff],
-            |row| {
-                let techniques_json: String = row.get(3)?;
-                let techniques: Vec<String> = serde_json::from_str(&techniques_json).unwrap_or_default();
-
-                Ok(CachedNeutralization {
-                    content_hash: row.get(0)?,
-                    original: row.get(1)?,
-                    neutralized: row.get(2)?,
-                    techniques,
-                    severity: row.get(4)?,
-                    created_at: row.get(5)?,
-                    hit_count: row.get(6)?,
-                })
-            }
-        ) {
-            Ok(cached) => {
-                // Update hit count
-                let _ = conn.execute(
-                    "UPDATE neutralization_cache SET hit_count = hit_count + 1 WHERE content_hash = ?1",
-                    params![content_hash]
-                );
+            placeholders
+        );
 
-                // Record hit
-                *self.hits.lock().unwrap() += 1;
-                let _ = conn.execute(
-                    "UPDATE cache_stats SET total_hits = total_hits + 1 WHERE id = 1",
-                    []
-                );
+        let mut values: Vec<rusqlite::types::Value> = bands.into_iter().map(Into::into).collect();
+        values.push(key.model.clone().into());
+        values.push(key.prompt_version.clone().into());
+        values.push(key.persona.clone().into());
+        values.push(cutoff.into());
 
-                Some(cached)
+        let mut stmt = conn.prepare(&sql).ok()?;
+        let candidates = stmt
+            .query_map(rusqlite::params_from_iter(values), |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, Vec<u8>>(1)?))
+            })
+            .ok()?;
+
+        candidates
+            .filter_map(Result::ok)
+            .map(|(cache_key, blob)| {
+                let similarity = signature_similarity(&signature, &signature_from_blob(&blob));
+                (cache_key, similarity)
+            })
+            .filter(|(_, similarity)| *similarity >= FUZZY_MIN_SIMILARITY)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    /// Count a lookup; `hit` is the entry served and whether it was fuzzy.
+    /// Writes reach SQLite in batches.
+    fn record_lookup(&self, hit: Option<(&str, bool)>) {
+        let flush_due = {
+            let Ok(mut pending) = self.pending.lock() else {
+                return;
+            };
+            match hit {
+                Some((cache_key, fuzzy)) => {
+                    *self.hits.lock().unwrap() += 1;
+                    pending.hits += 1;
+                    if fuzzy {
+                        *self.fuzzy_hits.lock().unwrap() += 1;
+                        pending.fuzzy_hits += 1;
+                    }
+                    let now = SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .map(|d| d.as_secs() as i64)
+                        .unwrap_or(0);
+                    let entry = pending.hit_counts.entry(cache_key.to_string()).or_insert((0, 0));
+                    entry.0 += 1;
+                    entry.1 = now;
+                }
+                None => {
+                    *self.misses.lock().unwrap() += 1;
+                    pending.misses += 1;
+                }
             }
-            Err(_) => {
-                // Record miss
-                *self.misses.lock().unwrap() += 1;
-                let _ = conn.execute(
-                    "UPDATE cache_stats SET total_misses = total_misses + 1 WHERE id = 1",
-                    []
-                );
+            pending.lookups += 1;
+            pending.lookups >= STATS_FLUSH_EVERY
+        };
 
-                None
+        if flush_due {
+            if let Err(e) = self.flush_stats() {
+                log::warn!("Failed to flush cache stats: {}", e);
             }
         }
     }
 
+    /// Write batched stats and hit counts to SQLite
+    pub fn flush_stats(&self) -> Result<(), String> {
+        let pending = std::mem::take(
+            &mut *self.pending.lock()
+                .map_err(|e| format!("Failed to acquire lock: {}", e))?,
+        );
+        if pending.lookups == 0 {
+            return Ok(());
+        }
+
+        let mut conn = self.conn.lock()
+            .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+
+        let mut write = || -> rusqlite::Result<()> {
+            let tx = conn.transaction()?;
+            tx.execute(
+                r#"
+                UPDATE cache_stats
+                SET total_hits = total_hits + ?1, fuzzy_hits = fuzzy_hits + ?2, total_misses = total_misses + ?3
+                WHERE id = 1
+                "#,
+                params![pending.hits, pending.fuzzy_hits, pending.misses],
+            )?;
+            for (cache_key, (count, accessed)) in &pending.hit_counts {
+                tx.execute(
+                    r#"
+                    UPDATE neutralization_cache
+                    SET hit_count = hit_count + ?1, last_accessed = MAX(last_accessed, ?2)
+                    WHERE cache_key = ?3
+                    "#,
+                    params![count, accessed, cache_key],
+                )?;
+            }
+            tx.commit()
+        };
+
+        write().map_err(|e| format!("Failed to write cache stats: {}", e))
+    }
+
     /// Store a neutralization result
     pub fn set(
         &self,
         original: &str,
+        key: &CacheKey,
         neutralized: &str,
         techniques: &[String],
         severity: i32,
+        source: &ContentSource,
     ) -> Result<(), String> {
         let content_hash = Self::hash_content(original);
+        let cache_key = key.composite(&content_hash);
+
+        // Nothing is written while encryption is locked, so plaintext never
+        // lands on disk
+        let privacy_mode = self.policy().privacy_mode;
+        let persisted_original = if privacy_mode { "" } else { original };
+        // Who wrote a post is as telling as the post itself
+        let author_hash = if privacy_mode { None } else { source.author_hash.as_deref() };
+        let Some((stored_original, stored_neutralized)) = self.seal(persisted_original, neutralized)? else {
+            return Ok(());
+        };
+
         let conn = self.conn.lock()
             .map_err(|e| format!("Failed to acquire lock: {}", e))?;
 
@@ -177,27 +1473,192 @@ impl NeutralizationCache {
             .map_err(|e| format!("Time error: {}", e))?
             .as_secs() as i64;
 
-        let techniques_json = serde_json::to_string(techniques)
-            .map_err(|e| format!("Failed to serialize techniques: {}", e))?;
+        let techniques_json = serde_json::to_string(techniques)
+            .map_err(|e| format!("Failed to serialize techniques: {}", e))?;
+
+        conn.execute(
+            r#"
+            INSERT OR REPLACE INTO neutralization_cache
+            (cache_key, content_hash, model, prompt_version, persona,
+             original, neutralized, techniques, severity, created_at, hit_count, last_accessed,
+             platform, content_kind, author_hash)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, 0, ?10, ?11, ?12, ?13)
+            "#,
+            params![
+                cache_key,
+                content_hash,
+                key.model,
+                key.prompt_version,
+                key.persona,
+                stored_original,
+                stored_neutralized,
+                techniques_json,
+                severity,
+                now,
+                source.platform,
+                source.kind.as_str(),
+                author_hash
+            ]
+        ).map_err(|e| format!("Failed to cache result: {}", e))?;
+
+        index_similarity(&conn, &cache_key, original)
+            .map_err(|e| format!("Failed to index cache entry: {}", e))?;
+
+        drop(conn);
+
+        if let Ok(mut hot) = self.hot.lock() {
+            hot.insert(
+                cache_key,
+                CachedNeutralization {
+                    content_hash,
+                    original: original.to_string(),
+                    neutralized: neutralized.to_string(),
+                    techniques: techniques.to_vec(),
+                    severity,
+                    created_at: now,
+                    hit_count: 0,
+                    model: key.model.clone(),
+                    prompt_version: key.prompt_version.clone(),
+                    persona: key.persona.clone(),
+                    source: ContentSource {
+                        author_hash: author_hash.map(str::to_string),
+                        ..source.clone()
+                    },
+                    similarity: None,
+                    action: None,
+                },
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Search live entries, newest first. Privacy-mode entries come back
+    /// with an empty original and only match on their output.
+    pub fn history(&self, query: &HistoryQuery) -> Result<HistoryPage, String> {
+        let limit = query.limit.clamp(1, HISTORY_MAX_PAGE_SIZE);
+        let words: Vec<String> = query
+            .text
+            .as_deref()
+            .unwrap_or("")
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect();
+
+        // Encrypted text isn't in the full-text index, so it is matched
+        // after decrypting
+        let encrypted = match self.encryption_status() {
+            EncryptionStatus { enabled: false, .. } => false,
+            EncryptionStatus { unlocked: false, .. } => {
+                return Err("Unlock the cache to view history".to_string());
+            }
+            _ => true,
+        };
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+
+        let mut conditions = vec!["created_at > ?"];
+        let mut values: Vec<Value> = vec![self.policy().expiry_cutoff(now).into()];
+        if !encrypted && !words.is_empty() {
+            conditions.push("rowid IN (SELECT rowid FROM history_fts WHERE history_fts MATCH ?)");
+            values.push(fts_query(&words).into());
+        }
+        if let Some(technique) = &query.technique {
+            conditions.push("EXISTS (SELECT 1 FROM json_each(techniques) WHERE json_each.value = ?)");
+            values.push(technique.clone().into());
+        }
+        if let Some(min) = query.min_severity {
+            conditions.push("severity >= ?");
+            values.push(min.into());
+        }
+        if let Some(max) = query.max_severity {
+            conditions.push("severity <= ?");
+            values.push(max.into());
+        }
+        if let Some(from) = query.from {
+            conditions.push("created_at >= ?");
+            values.push(from.into());
+        }
+        if let Some(to) = query.to {
+            conditions.push("created_at < ?");
+            values.push(to.into());
+        }
+        if let Some(platform) = &query.platform {
+            conditions.push("platform = ?");
+            values.push(platform.clone().into());
+        }
+        if let Some(kind) = query.kind {
+            conditions.push("content_kind = ?");
+            values.push(kind.as_str().to_string().into());
+        }
+        if let Some(author_hash) = &query.author_hash {
+            conditions.push("author_hash = ?");
+            values.push(author_hash.clone().into());
+        }
+        let filter = conditions.join(" AND ");
+
+        // Page in SQL unless the text still has to be matched in memory
+        let page_in_sql = !encrypted || words.is_empty();
+
+        let (total, rows) = {
+            let conn = self.conn.lock()
+                .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+
+            let total: i64 = if page_in_sql {
+                conn.query_row(
+                    &format!("SELECT COUNT(*) FROM neutralization_cache WHERE {}", filter),
+                    params_from_iter(values.iter()),
+                    |row| row.get(0),
+                )
+                .map_err(|e| format!("Failed to search history: {}", e))?
+            } else {
+                0
+            };
+
+            let mut sql = format!(
+                "SELECT {} FROM neutralization_cache WHERE {} ORDER BY created_at DESC, rowid DESC",
+                ENTRY_COLUMNS, filter
+            );
+            if page_in_sql {
+                sql.push_str(" LIMIT ? OFFSET ?");
+                values.push((limit as i64).into());
+                values.push((query.offset as i64).into());
+            }
 
-        conn.execute(
-            r#"
-            INSERT OR REPLACE INTO neutralization_cache
-            (content_hash, original, neutralized, techniques, severity, created_at, hit_count)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, 0)
-            "#,
-            params![content_hash, original, neutralized, techniques_json, severity, now]
-        ).map_err(|e| format!("Failed to cache result: {}", e))?;
+            let mut stmt = conn.prepare(&sql)
+                .map_err(|e| format!("Failed to search history: {}", e))?;
+            let rows = stmt
+                .query_map(params_from_iter(values.iter()), Self::entry_from_row)
+                .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+                .map_err(|e| format!("Failed to search history: {}", e))?;
+            (total as usize, rows)
+        };
 
-        // Prune old entries if needed
-        self.prune_if_needed(&conn);
+        let revealed = rows.into_iter().filter_map(|entry| self.reveal(entry));
+        let (items, total) = if page_in_sql {
+            (revealed.collect(), total)
+        } else {
+            let matching: Vec<CachedNeutralization> = revealed
+                .filter(|entry| {
+                    let original = entry.original.to_lowercase();
+                    let neutralized = entry.neutralized.to_lowercase();
+                    words.iter().all(|w| original.contains(w) || neutralized.contains(w))
+                })
+                .collect();
+            let total = matching.len();
+            (matching.into_iter().skip(query.offset).take(limit).collect(), total)
+        };
 
-        Ok(())
+        Ok(HistoryPage { items, total, offset: query.offset, limit })
     }
 
     /// Get cache statistics
     pub fn get_stats(&self) -> CacheStats {
         let hits = self.hits.lock().map(|g| *g).unwrap_or(0);
+        let fuzzy_hits = self.fuzzy_hits.lock().map(|g| *g).unwrap_or(0);
         let misses = self.misses.lock().map(|g| *g).unwrap_or(0);
         let total = hits + misses;
 
@@ -214,8 +1675,11 @@ impl NeutralizationCache {
         CacheStats {
             total_entries,
             cache_hits: hits,
+            exact_hits: hits - fuzzy_hits,
+            fuzzy_hits,
             cache_misses: misses,
             hit_rate: if total > 0 { hits as f64 / total as f64 } else { 0.0 },
+            hot_entries: self.hot.lock().map(|hot| hot.len()).unwrap_or(0),
         }
     }
 
@@ -227,50 +1691,566 @@ impl NeutralizationCache {
         conn.execute("DELETE FROM neutralization_cache", [])
             .map_err(|e| format!("Failed to clear cache: {}", e))?;
 
+        if let Ok(mut hot) = self.hot.lock() {
+            hot.clear();
+        }
+
+        Ok(())
+    }
+
+    /// Drop the entries selected by `target`, returning how many were removed
+    pub fn invalidate(&self, target: &CacheInvalidation) -> Result<usize, String> {
+        let conn = self.conn.lock()
+            .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+
+        let (column, value) = match target {
+            CacheInvalidation::Content(text) => ("content_hash", Self::hash_content(text)),
+            CacheInvalidation::Model(model) => ("model", model.clone()),
+            CacheInvalidation::PromptVersion(version) => ("prompt_version", version.clone()),
+            CacheInvalidation::Persona(persona) => ("persona", persona.clone()),
+        };
+
+        if let Ok(mut hot) = self.hot.lock() {
+            hot.retain(|entry| {
+                let field = match target {
+                    CacheInvalidation::Content(_) => &entry.content_hash,
+                    CacheInvalidation::Model(_) => &entry.model,
+                    CacheInvalidation::PromptVersion(_) => &entry.prompt_version,
+                    CacheInvalidation::Persona(_) => &entry.persona,
+                };
+                *field != value
+            });
+        }
+
+        let removed = conn
+            .execute(
+                &format!("DELETE FROM neutralization_cache WHERE {} = ?1", column),
+                params![value],
+            )
+            .map_err(|e| format!("Failed to invalidate cache: {}", e))?;
+
+        log::info!("Invalidated {} cache entries by {}", removed, column);
+        Ok(removed)
+    }
+
+    /// Encrypt the stored columns if encryption is on; `None` while locked
+    fn seal(&self, original: &str, neutralized: &str) -> Result<Option<(String, String)>, String> {
+        let cipher = self.cipher.lock()
+            .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+        match &*cipher {
+            CipherState::Disabled => Ok(Some((original.to_string(), neutralized.to_string()))),
+            CipherState::Locked => Ok(None),
+            CipherState::Unlocked(key) => {
+                let original = if original.is_empty() {
+                    String::new()
+                } else {
+                    crypto::encrypt_field(key, original)?
+                };
+                Ok(Some((original, crypto::encrypt_field(key, neutralized)?)))
+            }
+        }
+    }
+
+    /// Decrypt a loaded entry; `None` if it can't be read right now
+    fn reveal(&self, mut cached: CachedNeutralization) -> Option<CachedNeutralization> {
+        let encrypted = crypto::is_encrypted(&cached.original) || crypto::is_encrypted(&cached.neutralized);
+        if !encrypted {
+            return Some(cached);
+        }
+
+        let cipher = self.cipher.lock().ok()?;
+        let CipherState::Unlocked(key) = &*cipher else {
+            return None;
+        };
+        cached.original = crypto::decrypt_field(key, &cached.original).ok()?;
+        cached.neutralized = crypto::decrypt_field(key, &cached.neutralized).ok()?;
+        Some(cached)
+    }
+
+    pub fn encryption_status(&self) -> EncryptionStatus {
+        match self.cipher.lock().as_deref() {
+            Ok(CipherState::Unlocked(_)) => EncryptionStatus { enabled: true, unlocked: true },
+            Ok(CipherState::Locked) => EncryptionStatus { enabled: true, unlocked: false },
+            _ => EncryptionStatus { enabled: false, unlocked: false },
+        }
+    }
+
+    fn keyfile(&self) -> Result<PathBuf, String> {
+        self.path
+            .as_ref()
+            .map(|path| keyfile_path(path))
+            .ok_or_else(|| "Encryption is not available for an in-memory cache".to_string())
+    }
+
+    /// Start encrypting the cache under a parental passphrase. Existing
+    /// entries are encrypted and plaintext backups are wiped.
+    pub fn enable_encryption(&self, passphrase: &str) -> Result<(), String> {
+        let keyfile = self.keyfile()?;
+        if self.encryption_status().enabled {
+            return Err("Cache encryption is already enabled".to_string());
+        }
+
+        let key = DataKey::generate();
+        Keyfile::wrap(&key, passphrase)?.save(&keyfile)?;
+
+        let mut conn = self.conn.lock()
+            .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+        if let Err(e) = reencrypt_all(&mut conn, None, Some(&key)) {
+            let _ = std::fs::remove_file(&keyfile);
+            return Err(e);
+        }
+        purge_free_space(&conn)?;
+        drop(conn);
+
+        self.wipe_plaintext_copies()?;
+        *self.cipher.lock().map_err(|e| format!("Failed to acquire lock: {}", e))? =
+            CipherState::Unlocked(key);
+        log::info!("Cache encryption enabled");
+        Ok(())
+    }
+
+    /// Load the data key for this session
+    pub fn unlock(&self, passphrase: &str) -> Result<(), String> {
+        let key = Keyfile::load(&self.keyfile()?)?.unwrap_key(passphrase)?;
+        *self.cipher.lock().map_err(|e| format!("Failed to acquire lock: {}", e))? =
+            CipherState::Unlocked(key);
+        Ok(())
+    }
+
+    /// Forget the data key and the decrypted hot tier until the next unlock
+    pub fn lock(&self) {
+        if let Ok(mut cipher) = self.cipher.lock() {
+            if matches!(*cipher, CipherState::Unlocked(_)) {
+                *cipher = CipherState::Locked;
+            }
+        }
+        if let Ok(mut hot) = self.hot.lock() {
+            hot.clear();
+        }
+    }
+
+    /// Re-encrypt everything under a fresh data key, optionally changing the
+    /// passphrase. If the app dies between the database commit and the
+    /// keyfile write, old entries become unreadable misses and age out.
+    pub fn rotate_key(&self, passphrase: &str, new_passphrase: Option<&str>) -> Result<(), String> {
+        let keyfile = self.keyfile()?;
+        let old_key = Keyfile::load(&keyfile)?.unwrap_key(passphrase)?;
+        let new_key = DataKey::generate();
+        let wrapped = Keyfile::wrap(&new_key, new_passphrase.unwrap_or(passphrase))?;
+
+        let mut conn = self.conn.lock()
+            .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+        reencrypt_all(&mut conn, Some(&old_key), Some(&new_key))?;
+        purge_free_space(&conn)?;
+        wrapped.save(&keyfile)?;
+        drop(conn);
+
+        *self.cipher.lock().map_err(|e| format!("Failed to acquire lock: {}", e))? =
+            CipherState::Unlocked(new_key);
+        log::info!("Cache encryption key rotated");
+        Ok(())
+    }
+
+    /// Decrypt all entries and remove the keyfile
+    pub fn disable_encryption(&self, passphrase: &str) -> Result<(), String> {
+        let keyfile = self.keyfile()?;
+        let key = Keyfile::load(&keyfile)?.unwrap_key(passphrase)?;
+
+        let mut conn = self.conn.lock()
+            .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+        reencrypt_all(&mut conn, Some(&key), None)?;
+        // Entries the key couldn't open were dropped; don't leave them behind
+        purge_free_space(&conn)?;
+        drop(conn);
+
+        crypto::wipe_file(&keyfile)?;
+        *self.cipher.lock().map_err(|e| format!("Failed to acquire lock: {}", e))? =
+            CipherState::Disabled;
+        log::info!("Cache encryption disabled");
+        Ok(())
+    }
+
+    /// Erase every entry, backup and the keyfile. Without the key, any
+    /// encrypted data the filesystem still holds can't be read back.
+    pub fn secure_wipe(&self) -> Result<(), String> {
+        let conn = self.conn.lock()
+            .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+        conn.execute_batch(
+            r#"
+            DELETE FROM neutralization_cache;
+            UPDATE cache_stats SET total_hits = 0, total_misses = 0, fuzzy_hits = 0 WHERE id = 1;
+            "#,
+        )
+        .map_err(|e| format!("Failed to wipe cache: {}", e))?;
+        purge_free_space(&conn)?;
+        drop(conn);
+
+        if let Ok(mut hot) = self.hot.lock() {
+            hot.clear();
+        }
+        if let Ok(mut pending) = self.pending.lock() {
+            *pending = PendingStats::default();
+        }
+        for counter in [&self.hits, &self.fuzzy_hits, &self.misses] {
+            if let Ok(mut value) = counter.lock() {
+                *value = 0;
+            }
+        }
+
+        self.wipe_plaintext_copies()?;
+        if let Ok(keyfile) = self.keyfile() {
+            crypto::wipe_file(&keyfile)?;
+        }
+        *self.cipher.lock().map_err(|e| format!("Failed to acquire lock: {}", e))? =
+            CipherState::Disabled;
+        log::info!("Cache securely wiped");
+        Ok(())
+    }
+
+    /// Migration backups and set-aside databases next to the cache file
+    fn wipe_plaintext_copies(&self) -> Result<(), String> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+            return Ok(());
+        };
+        let prefix = format!("{}.", name.to_string_lossy());
+
+        let entries = std::fs::read_dir(dir)
+            .map_err(|e| format!("Failed to list cache directory: {}", e))?;
+        for entry in entries.flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if file_name.starts_with(&prefix)
+                && (file_name.ends_with(".bak") || file_name.ends_with(".broken"))
+            {
+                crypto::wipe_file(&entry.path())?;
+            }
+        }
         Ok(())
     }
 
-    /// Remove expired and excess entries
-    fn prune_if_needed(&self, conn: &Connection) {
+    /// Write live entries to a portable JSONL file. Returns the entry count.
+    pub fn export_to(&self, path: &Path) -> Result<usize, String> {
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .map(|d| d.as_secs() as i64)
             .unwrap_or(0);
+        let cutoff = self.policy().expiry_cutoff(now);
 
-        let cutoff = now - (CACHE_TTL_HOURS * 3600);
+        let rows: Vec<(ExportedEntry, Option<Vec<u8>>)> = {
+            let conn = self.conn.lock()
+                .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+            let mut stmt = conn
+                .prepare(
+                    r#"
+                    SELECT c.content_hash, c.model, c.prompt_version, c.persona, c.neutralized,
+                           c.techniques, c.severity, c.created_at, s.signature
+                    FROM neutralization_cache c
+                    LEFT JOIN similarity_index s ON s.cache_key = c.cache_key
+                    WHERE c.created_at > ?1
+                    ORDER BY c.created_at
+                    "#,
+                )
+                .map_err(|e| format!("Failed to read cache: {}", e))?;
+            let rows = stmt
+                .query_map(params![cutoff], |row| {
+                    let techniques: String = row.get(5)?;
+                    Ok((
+                        ExportedEntry {
+                            content_hash: row.get(0)?,
+                            model: row.get(1)?,
+                            prompt_version: row.get(2)?,
+                            persona: row.get(3)?,
+                            neutralized: row.get(4)?,
+                            techniques: serde_json::from_str(&techniques).unwrap_or_default(),
+                            severity: row.get(6)?,
+                            created_at: row.get(7)?,
+                            signature: None,
+                        },
+                        row.get(8)?,
+                    ))
+                })
+                .and_then(|rows| rows.collect::<Result<_, _>>())
+                .map_err(|e| format!("Failed to read cache: {}", e))?;
+            rows
+        };
 
-        // Remove expired entries
-        let _ = conn.execute(
-            "DELETE FROM neutralization_cache WHERE created_at < ?1",
-            params![cutoff]
+        let mut lines = Vec::with_capacity(rows.len() + 1);
+        lines.push(
+            serde_json::to_string(&ExportHeader {
+                format: EXPORT_FORMAT.to_string(),
+                version: EXPORT_VERSION,
+                exported_at: now,
+                entries: rows.len(),
+            })
+            .map_err(|e| format!("Failed to write export: {}", e))?,
         );
 
-        // Remove excess entries (keep most recently used)
-        let count: i64 = conn.query_row(
-            "SELECT COUNT(*) FROM neutralization_cache",
-            [],
-            |row| row.get(0)
-        ).unwrap_or(0);
+        {
+            let cipher = self.cipher.lock()
+                .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+            for (mut entry, signature) in rows {
+                if crypto::is_encrypted(&entry.neutralized) {
+                    let CipherState::Unlocked(key) = &*cipher else {
+                        return Err("Unlock the cache before exporting it".to_string());
+                    };
+                    entry.neutralized = crypto::decrypt_field(key, &entry.neutralized)?;
+                }
+                entry.signature = signature
+                    .filter(|blob| !blob.is_empty())
+                    .map(hex::encode);
+                lines.push(
+                    serde_json::to_string(&entry)
+                        .map_err(|e| format!("Failed to write export: {}", e))?,
+                );
+            }
+        }
 
-        if count > MAX_CACHE_ENTRIES {
-            let to_remove = count - MAX_CACHE_ENTRIES;
-            let _ = conn.execute(
-                r#"
-                DELETE FROM neutralization_cache WHERE content_hash IN (
-                    SELECT content_hash FROM neutralization_cache
-                    ORDER BY hit_count ASC, created_at ASC
-                    LIMIT ?1
+        let count = lines.len() - 1;
+        lines.push(String::new());
+        std::fs::write(path, lines.join("\n"))
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+        log::info!("Exported {} cache entries to {:?}", count, path);
+        Ok(count)
+    }
+
+    /// Merge an export file into this cache. On conflict the newer entry
+    /// wins; entries from other prompt versions are skipped.
+    pub fn import_from(&self, path: &Path, prompt_version: &str) -> Result<ImportReport, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let mut lines = contents.lines().filter(|line| !line.trim().is_empty());
+
+        let header: ExportHeader = lines
+            .next()
+            .and_then(|line| serde_json::from_str(line).ok())
+            .ok_or("Not a FeelingWise cache export")?;
+        if header.format != EXPORT_FORMAT {
+            return Err("Not a FeelingWise cache export".to_string());
+        }
+        if header.version > EXPORT_VERSION {
+            return Err(format!(
+                "Cache export version {} is newer than this app supports",
+                header.version
+            ));
+        }
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        let cutoff = self.policy().expiry_cutoff(now);
+        let mut report = ImportReport::default();
+
+        let mut conn = self.conn.lock()
+            .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+        let tx = conn.transaction()
+            .map_err(|e| format!("Failed to start import: {}", e))?;
+
+        for line in lines {
+            let Ok(entry) = serde_json::from_str::<ExportedEntry>(line) else {
+                report.skipped_invalid += 1;
+                continue;
+            };
+            if entry.prompt_version != prompt_version {
+                report.skipped_incompatible += 1;
+                continue;
+            }
+            if entry.created_at <= cutoff {
+                report.skipped_expired += 1;
+                continue;
+            }
+            // A future date would never expire and would win every conflict
+            if entry.created_at > now {
+                report.skipped_invalid += 1;
+                continue;
+            }
+
+            let Some((_, neutralized)) = self.seal("", &entry.neutralized)? else {
+                return Err("Unlock the cache before importing into it".to_string());
+            };
+            let key = CacheKey {
+                model: entry.model.clone(),
+                prompt_version: entry.prompt_version.clone(),
+                persona: entry.persona.clone(),
+            };
+            let cache_key = key.composite(&entry.content_hash);
+            let techniques = serde_json::to_string(&entry.techniques)
+                .map_err(|e| format!("Failed to serialize techniques: {}", e))?;
+
+            // The local original, if any, is kept
+            let changed = tx
+                .execute(
+                    r#"
+                    INSERT INTO neutralization_cache
+                    (cache_key, content_hash, model, prompt_version, persona,
+                     original, neutralized, techniques, severity, created_at, hit_count, last_accessed)
+                    VALUES (?1, ?2, ?3, ?4, ?5, '', ?6, ?7, ?8, ?9, 0, ?9)
+                    ON CONFLICT(cache_key) DO UPDATE SET
+                        neutralized = excluded.neutralized,
+                        techniques = excluded.techniques,
+                        severity = excluded.severity,
+                        created_at = excluded.created_at
+                    WHERE excluded.created_at > neutralization_cache.created_at
+                    "#,
+                    params![
+                        cache_key,
+                        entry.content_hash,
+                        entry.model,
+                        entry.prompt_version,
+                        entry.persona,
+                        neutralized,
+                        techniques,
+                        entry.severity.clamp(0, 10),
+                        entry.created_at
+                    ],
                 )
-                "#,
-                params![to_remove]
-            );
+                .map_err(|e| format!("Failed to import cache entry: {}", e))?;
+
+            if changed == 0 {
+                report.kept_local += 1;
+                continue;
+            }
+            report.imported += 1;
+
+            let signature = entry
+                .signature
+                .and_then(|sig| hex::decode(sig).ok())
+                .map(|blob| signature_from_blob(&blob))
+                .filter(|sig| sig.len() == MINHASH_SIZE)
+                .unwrap_or_default();
+            index_signature(&tx, &cache_key, &signature)
+                .map_err(|e| format!("Failed to index cache entry: {}", e))?;
+        }
+
+        tx.commit().map_err(|e| format!("Failed to commit import: {}", e))?;
+        drop(conn);
+
+        // Entries that changed may be stale in the hot tier
+        if let Ok(mut hot) = self.hot.lock() {
+            hot.clear();
+        }
+
+        log::info!("Imported cache from {:?}: {:?}", path, report);
+        Ok(report)
+    }
+
+    /// Remove expired entries, then evict until within the entry and size
+    /// limits. Returns how many entries were removed.
+    pub fn prune(&self) -> Result<usize, String> {
+        let policy = self.policy();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        let cutoff = policy.expiry_cutoff(now);
+
+        let conn = self.conn.lock()
+            .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+
+        let prune = || -> rusqlite::Result<usize> {
+            let count = |conn: &Connection| -> rusqlite::Result<i64> {
+                conn.query_row("SELECT COUNT(*) FROM neutralization_cache", [], |row| row.get(0))
+            };
+            let evict = |limit: i64| -> rusqlite::Result<usize> {
+                conn.execute(
+                    &format!(
+                        r#"
+                        DELETE FROM neutralization_cache WHERE cache_key IN (
+                            SELECT cache_key FROM neutralization_cache
+                            ORDER BY {}
+                            LIMIT ?1
+                        )
+                        "#,
+                        policy.eviction_order()
+                    ),
+                    params![limit],
+                )
+            };
+
+            let mut removed = conn.execute(
+                "DELETE FROM neutralization_cache WHERE created_at <= ?1",
+                params![cutoff],
+            )?;
+
+            let excess = count(&conn)? - i64::from(policy.max_entries);
+            if excess > 0 {
+                removed += evict(excess)?;
+            }
+
+            // Over the size limit: evict a tenth of what's left at a time
+            while used_bytes(&conn)? > policy.max_bytes {
+                let remaining = count(&conn)?;
+                if remaining == 0 {
+                    break;
+                }
+                removed += evict((remaining / 10).max(1))?;
+            }
+
+            if removed > 0 {
+                let pragma = |name: &str| conn.query_row(&format!("PRAGMA {}", name), [], |row| row.get::<_, i64>(0));
+                // Give space back to the filesystem once a quarter of the file is free
+                if pragma("freelist_count")? * 4 > pragma("page_count")? {
+                    conn.execute_batch("VACUUM")?;
+                    rebuild_history_index(&conn)?;
+                }
+                if self.path.is_some() {
+                    conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+                }
+            }
+            Ok(removed)
+        };
+
+        let removed = prune().map_err(|e| format!("Failed to prune cache: {}", e))?;
+        drop(conn);
+
+        if let Ok(mut hot) = self.hot.lock() {
+            hot.retain(|entry| entry.created_at > cutoff);
         }
+        Ok(removed)
+    }
+
+    /// Entry count and disk usage
+    pub fn usage(&self) -> Result<CacheUsage, String> {
+        let policy = self.policy();
+        let conn = self.conn.lock()
+            .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+
+        let entries = conn
+            .query_row("SELECT COUNT(*) FROM neutralization_cache", [], |row| row.get(0))
+            .map_err(|e| format!("Failed to count cache entries: {}", e))?;
+        let used_bytes = used_bytes(&conn)
+            .map_err(|e| format!("Failed to read cache size: {}", e))?;
+
+        let file_bytes = self
+            .path
+            .as_ref()
+            .map(|path| {
+                ["", "-wal"]
+                    .iter()
+                    .filter_map(|suffix| {
+                        let mut file = path.as_os_str().to_owned();
+                        file.push(suffix);
+                        std::fs::metadata(PathBuf::from(file)).ok()
+                    })
+                    .map(|meta| meta.len())
+                    .sum()
+            })
+            .unwrap_or(used_bytes);
+
+        Ok(CacheUsage {
+            entries,
+            file_bytes,
+            used_bytes,
+            max_entries: policy.max_entries,
+            max_bytes: policy.max_bytes,
+        })
     }
 }
 
 // In-memory LRU cache for hot entries
-use std::collections::HashMap;
-
 pub struct LruCache {
     map: HashMap<String, (CachedNeutralization, u64)>,
     max_size: usize,
@@ -312,15 +2292,477 @@ impl LruCache {
         self.map.insert(key, (value, self.access_counter));
     }
 
-    pub fn contains(&self, key: &str) -> bool {
-        self.map.contains_key(key)
+    /// Keep only the entries for which `keep` returns true
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&CachedNeutralization) -> bool,
+    {
+        self.map.retain(|_, (entry, _)| keep(entry));
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
     }
 
     pub fn len(&self) -> usize {
         self.map.len()
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_db() -> PathBuf {
+        std::env::temp_dir().join(format!("fw-cache-{}.db", uuid::Uuid::new_v4()))
+    }
+
+    fn key(model: &str, persona: &str) -> CacheKey {
+        CacheKey {
+            model: model.to_string(),
+            prompt_version: "v1".to_string(),
+            persona: persona.to_string(),
+        }
+    }
+
+    fn source(platform: &str, kind: ContentKind) -> ContentSource {
+        ContentSource {
+            platform: platform.to_string(),
+            kind,
+            author_hash: Some("@NewsBot".to_string()),
+        }
+        .normalized()
+    }
+
+    #[test]
+    fn test_normalizes_twitter_variants() {
+        let copied = "BREAKING: they are hiding it 🚨\u{200B}  https://x.com/someone/status/123?s=20&t=AbC\n\nShow more";
+        let shared = "BREAKING: they are hiding it 🚨 https://twitter.com/someone/status/123";
+        assert_eq!(normalize_content(copied), normalize_content(shared));
+        assert_eq!(
+            normalize_content(shared),
+            "BREAKING: they are hiding it 🚨 https://twitter.com/someone/status/123"
+        );
+    }
+
+    #[test]
+    fn test_normalizes_facebook_variants() {
+        let mobile = "Everyone needs to read this https://m.facebook.com/story.php?story_fbid=1&id=2&fbclid=IwAR0 … See more";
+        let desktop = "Everyone needs to read this https://www.facebook.com/story.php?id=2&story_fbid=1";
+        assert_eq!(normalize_content(mobile), normalize_content(desktop));
+    }
+
+    #[test]
+    fn test_normalizes_youtube_variants() {
+        // Fullwidth letters fold to ASCII under NFKC
+        let short = "Ｗａｔｃｈ before it's deleted https://youtu.be/dQw4w9WgXcQ?si=xyz ...more";
+        let long = "Watch before it's deleted https://www.youtube.com/watch?v=dQw4w9WgXcQ&feature=share";
+        assert_eq!(normalize_content(short), normalize_content(long));
+        assert_eq!(
+            NeutralizationCache::hash_content(short),
+            NeutralizationCache::hash_content(long)
+        );
+    }
+
+    #[test]
+    fn test_keeps_meaningful_text() {
+        assert_eq!(normalize_content("Quoth the raven, nevermore"), "Quoth the raven, nevermore");
+        assert_eq!(normalize_content("I want more"), "I want more");
+    }
+
+    #[test]
+    fn test_near_duplicate_hit() {
+        let path = temp_db();
+        let cache = NeutralizationCache::new(Some(path.clone())).unwrap();
+        let phi = key("phi3:mini", "adult");
+        let post = "The city council voted last night to close three public libraries \
+                    and nobody in the neighborhood was asked about it beforehand";
+        cache.set(post, &phi, "The council voted to close three libraries.", &[], 3, &ContentSource::default()).unwrap();
+
+        let repost = format!("RT: {} 😡", post);
+        let hit = cache.get(&repost, &phi).expect("near-duplicate should hit");
+        let similarity = hit.similarity.unwrap();
+        assert!((FUZZY_MIN_SIMILARITY..1.0).contains(&similarity));
+        assert_eq!(hit.original, repost);
+        assert!(cache.get(&repost, &key("phi3:mini", "child")).is_none());
+        assert!(cache.get("Completely different text about the weekend weather forecast for the coast", &phi).is_none());
+
+        assert_eq!(cache.get(post, &phi).unwrap().similarity, Some(1.0));
+        let stats = cache.get_stats();
+        assert_eq!((stats.exact_hits, stats.fuzzy_hits, stats.cache_hits), (1, 1, 2));
+
+        drop(cache);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_hot_tier_and_batched_stats() {
+        let path = temp_db();
+        let cache = NeutralizationCache::new(Some(path.clone())).unwrap();
+        let phi = key("phi3:mini", "adult");
+        cache.set("Hello there", &phi, "Hello there", &[], 0, &ContentSource::default()).unwrap();
+
+        // Served from memory even once the row is gone from SQLite
+        cache.conn.lock().unwrap().execute("DELETE FROM neutralization_cache", []).unwrap();
+        let lookup = phi.clone();
+        assert!(cache.run(move |c| c.get("Hello there", &lookup)).await.unwrap().is_some());
+        assert!(cache.get("Goodbye", &phi).is_none());
+        assert_eq!(cache.get_stats().hot_entries, 1);
+
+        let persisted = |c: &NeutralizationCache| -> (i64, i64) {
+            c.conn.lock().unwrap()
+                .query_row("SELECT total_hits, total_misses FROM cache_stats WHERE id = 1", [], |r| {
+                    Ok((r.get(0)?, r.get(1)?))
+                })
+                .unwrap()
+        };
+        assert_eq!(persisted(&cache), (0, 0));
+        cache.flush_stats().unwrap();
+        assert_eq!(persisted(&cache), (1, 1));
+
+        let mode: String = cache.conn.lock().unwrap()
+            .query_row("PRAGMA journal_mode", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(mode, "wal");
+
+        drop(cache);
+        for suffix in ["", "-wal", "-shm"] {
+            let mut file = path.as_os_str().to_owned();
+            file.push(suffix);
+            let _ = std::fs::remove_file(PathBuf::from(file));
+        }
+    }
+
+    #[test]
+    fn test_prune_applies_policy() {
+        let cache = NeutralizationCache::in_memory().unwrap();
+        let phi = key("phi3:mini", "adult");
+        for text in ["first post", "second post", "third post"] {
+            cache.set(text, &phi, text, &[], 0, &ContentSource::default()).unwrap();
+        }
+        // "second post" becomes the most used and most recent
+        for _ in 0..3 {
+            cache.get("second post", &phi).unwrap();
+        }
+        cache.flush_stats().unwrap();
+
+        cache.set_policy(CachePolicy {
+            max_entries: 1,
+            eviction: EvictionPolicy::Lfu,
+            ..CachePolicy::default()
+        }).unwrap();
+        assert_eq!(cache.prune().unwrap(), 2);
+        let usage = cache.usage().unwrap();
+        assert_eq!((usage.entries, usage.max_entries), (1, 1));
+        assert!(cache.get("second post", &phi).is_some());
+
+        cache.set_policy(CachePolicy { max_bytes: 0, ..CachePolicy::default() }).unwrap();
+        assert_eq!(cache.prune().unwrap(), 1);
+        assert_eq!(cache.usage().unwrap().entries, 0);
+    }
+
+    #[test]
+    fn test_history_search_after_vacuuming_prune() {
+        let cache = NeutralizationCache::in_memory().unwrap();
+        let phi = key("phi3:mini", "adult");
+        let filler = "padding ".repeat(200);
+        for i in 0..60 {
+            let text = format!("marker{} {}", i, filler);
+            cache.set(&text, &phi, &text, &[], 0, &ContentSource::default()).unwrap();
+        }
+
+        cache.set_policy(CachePolicy { max_entries: 3, ..CachePolicy::default() }).unwrap();
+        assert_eq!(cache.prune().unwrap(), 57);
+        let freelist: i64 = cache.conn.lock().unwrap()
+            .query_row("PRAGMA freelist_count", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(freelist, 0, "prune should have vacuumed");
+
+        // The index must still point at the surviving entries
+        for i in 57..60 {
+            let found = cache.history(&HistoryQuery { text: Some(format!("marker{}", i)), ..Default::default() }).unwrap();
+            assert_eq!(found.total, 1);
+            assert!(found.items[0].original.starts_with(&format!("marker{} ", i)));
+        }
+        let gone = cache.history(&HistoryQuery { text: Some("marker0".into()), ..Default::default() }).unwrap();
+        assert_eq!(gone.total, 0);
+    }
+
+    #[test]
+    fn test_encryption_lifecycle() {
+        let dir = std::env::temp_dir().join(format!("fw-cache-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("cache.db");
+        let phi = key("phi3:mini", "adult");
+
+        let cache = NeutralizationCache::new(Some(path.clone())).unwrap();
+        cache.set("SECRET POST!!!", &phi, "Secret post.", &[], 2, &ContentSource::default()).unwrap();
+        std::fs::write(backup_path(&path, 1), b"plaintext backup").unwrap();
+
+        cache.enable_encryption("parent passphrase").unwrap();
+        assert!(!backup_path(&path, 1).exists());
+        let stored: String = cache.conn.lock().unwrap()
+            .query_row("SELECT original FROM neutralization_cache", [], |r| r.get(0))
+            .unwrap();
+        assert!(crypto::is_encrypted(&stored));
+
+        // After a restart the cache is locked and bypassed until unlocked
+        drop(cache);
+        let cache = NeutralizationCache::new(Some(path.clone())).unwrap();
+        assert!(!cache.encryption_status().unlocked);
+        assert!(cache.get("SECRET POST!!!", &phi).is_none());
+        assert!(cache.unlock("wrong passphrase").is_err());
+        cache.unlock("parent passphrase").unwrap();
+        assert_eq!(cache.get("SECRET POST!!!", &phi).unwrap().original, "SECRET POST!!!");
+
+        cache.rotate_key("parent passphrase", Some("new passphrase")).unwrap();
+        cache.lock();
+        assert!(cache.unlock("parent passphrase").is_err());
+        cache.unlock("new passphrase").unwrap();
+        assert_eq!(cache.get("SECRET POST!!!", &phi).unwrap().neutralized, "Secret post.");
+
+        cache.disable_encryption("new passphrase").unwrap();
+        assert!(!keyfile_path(&path).exists());
+        let stored: String = cache.conn.lock().unwrap()
+            .query_row("SELECT original FROM neutralization_cache", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(stored, "SECRET POST!!!");
+        let mut wal = path.as_os_str().to_owned();
+        wal.push("-wal");
+        assert_eq!(std::fs::metadata(PathBuf::from(wal)).map(|m| m.len()).unwrap_or(0), 0);
+
+        cache.secure_wipe().unwrap();
+        assert!(!cache.encryption_status().enabled);
+        assert!(!keyfile_path(&path).exists());
+        assert_eq!(cache.usage().unwrap().entries, 0);
+
+        drop(cache);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_privacy_mode_scrubs_and_skips_originals() {
+        let cache = NeutralizationCache::in_memory().unwrap();
+        let phi = key("phi3:mini", "adult");
+        cache.set("stored before privacy mode", &phi, "before", &[], 0, &ContentSource::default()).unwrap();
+
+        cache.set_policy(CachePolicy { privacy_mode: true, ..CachePolicy::default() }).unwrap();
+        cache.set("stored during privacy mode", &phi, "during", &[], 0, &ContentSource::default()).unwrap();
+
+        let originals: Vec<String> = cache.conn.lock().unwrap()
+            .prepare("SELECT original FROM neutralization_cache").unwrap()
+            .query_map([], |r| r.get(0)).unwrap()
+            .collect::<Result<_, _>>().unwrap();
+        assert_eq!(originals, vec!["", ""]);
+
+        let hit = cache.get("stored before privacy mode", &phi).unwrap();
+        assert_eq!((hit.original.as_str(), hit.neutralized.as_str()), ("stored before privacy mode", "before"));
+
+        // The scrubbed words are gone from the full-text index too
+        let leaked: i64 = cache.conn.lock().unwrap()
+            .query_row("SELECT COUNT(*) FROM history_fts_data WHERE instr(block, CAST('stored' AS BLOB)) > 0", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(leaked, 0);
+    }
+
+    #[test]
+    fn test_export_import_merges_by_newest() {
+        let phi = key("phi3:mini", "adult");
+        let post = "The city council voted last night to close three public libraries \
+                    and nobody in the neighborhood was asked about it beforehand";
+
+        let laptop = NeutralizationCache::in_memory().unwrap();
+        laptop.set(post, &phi, "from laptop", &[], 3, &ContentSource::default()).unwrap();
+        laptop.set("old prompt entry", &CacheKey { prompt_version: "v0".into(), ..phi.clone() }, "old", &[], 0, &ContentSource::default()).unwrap();
+
+        let file = std::env::temp_dir().join(format!("fw-export-{}.jsonl", uuid::Uuid::new_v4()));
+        assert_eq!(laptop.export_to(&file).unwrap(), 2);
+        let exported = std::fs::read_to_string(&file).unwrap();
+        assert!(!exported.contains("city council"));
+
+        let desktop = NeutralizationCache::in_memory().unwrap();
+        let report = desktop.import_from(&file, "v1").unwrap();
+        assert_eq!((report.imported, report.skipped_incompatible), (1, 1));
+
+        let hit = desktop.get(post, &phi).unwrap();
+        assert_eq!((hit.neutralized.as_str(), hit.original.as_str()), ("from laptop", post));
+        assert!(desktop.get(&format!("RT: {}", post), &phi).is_some());
+
+        // A newer local entry is kept over the imported one
+        desktop.conn.lock().unwrap()
+            .execute("UPDATE neutralization_cache SET neutralized = 'local', created_at = created_at + 10", [])
+            .unwrap();
+        let report = desktop.import_from(&file, "v1").unwrap();
+        assert_eq!((report.imported, report.kept_local), (0, 1));
+
+        // Severity is clamped, and entries dated in the future are refused
+        // rather than winning every conflict
+        let rewrite = |shift: i64| {
+            let lines: Vec<String> = exported
+                .lines()
+                .map(|line| match serde_json::from_str::<ExportedEntry>(line) {
+                    Ok(mut entry) => {
+                        entry.created_at += shift;
+                        entry.severity = 99;
+                        serde_json::to_string(&entry).unwrap()
+                    }
+                    Err(_) => line.to_string(),
+                })
+                .collect();
+            std::fs::write(&file, lines.join("\n")).unwrap();
+        };
+        rewrite(0);
+        let fresh = NeutralizationCache::in_memory().unwrap();
+        assert_eq!(fresh.import_from(&file, "v1").unwrap().imported, 1);
+        assert_eq!(fresh.get(post, &phi).unwrap().severity, 10);
+
+        rewrite(365 * 24 * 60 * 60);
+        let report = desktop.import_from(&file, "v1").unwrap();
+        assert_eq!((report.imported, report.skipped_invalid), (0, 1));
+        assert_eq!(desktop.get(post, &phi).unwrap().neutralized, "local");
+
+        let _ = std::fs::remove_file(file);
+    }
+
+    #[test]
+    fn test_history_search_and_filters() {
+        let cache = NeutralizationCache::in_memory().unwrap();
+        let phi = key("phi3:mini", "adult");
+        let fear = vec!["fear_mongering".to_string()];
+        cache.set("They are COMING for your kids", &phi, "A group is campaigning.", &fear, 8, &source("facebook", ContentKind::Post)).unwrap();
+        cache.set("Vaccines are a HOAX!!!", &phi, "Some doubt vaccine safety.", &[], 5, &source("twitter", ContentKind::Comment)).unwrap();
+        cache.set("Nice weather today", &phi, "Nice weather today", &[], 0, &source("twitter", ContentKind::Post)).unwrap();
+        // Replacing an entry must not leave a stale index row behind
+        cache.set("Vaccines are a HOAX!!!", &phi, "Some question vaccines.", &[], 6, &source("twitter", ContentKind::Comment)).unwrap();
+
+        let search = |query: HistoryQuery| cache.history(&query).unwrap();
+        let text = |t: &str| HistoryQuery { text: Some(t.into()), ..Default::default() };
+
+        assert_eq!(search(HistoryQuery::default()).total, 3);
+        assert_eq!(search(text("vacc")).total, 1);
+        assert_eq!(search(text("campaigning kids")).total, 1);
+        assert_eq!(search(text("doubt")).total, 0);
+        assert_eq!(search(text("\"unbalanced OR")).total, 0);
+        assert_eq!(search(HistoryQuery { technique: Some("fear_mongering".into()), ..Default::default() }).total, 1);
+        assert_eq!(search(HistoryQuery { min_severity: Some(5), max_severity: Some(6), ..Default::default() }).total, 1);
+        assert_eq!(search(HistoryQuery { platform: Some("twitter".into()), ..Default::default() }).total, 2);
+        assert_eq!(search(HistoryQuery { kind: Some(ContentKind::Comment), ..Default::default() }).total, 1);
+        let author = source("", ContentKind::Unknown).author_hash;
+        assert_eq!(author.as_ref().map(String::len), Some(64));
+        assert_eq!(ContentSource { author_hash: author.clone(), ..Default::default() }.normalized().author_hash, author);
+        assert_eq!(search(HistoryQuery { author_hash: author, ..Default::default() }).total, 3);
+        assert_eq!(search(HistoryQuery::default()).items[0].source.kind, ContentKind::Comment);
+        assert_eq!(search(HistoryQuery { to: Some(0), ..Default::default() }).total, 0);
+
+        let page = search(HistoryQuery { offset: 1, limit: 1, ..Default::default() });
+        assert_eq!((page.items.len(), page.total), (1, 3));
+
+        // Encrypted entries are matched after decrypting
+        let dir = std::env::temp_dir().join(format!("fw-cache-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let encrypted = NeutralizationCache::new(Some(dir.join("cache.db"))).unwrap();
+        encrypted.set("SHOCKING truth", &phi, "A claim.", &[], 3, &ContentSource::default()).unwrap();
+        encrypted.enable_encryption("parent passphrase").unwrap();
+        let found = encrypted.history(&text("shocking")).unwrap();
+        assert_eq!(found.items[0].original, "SHOCKING truth");
+        encrypted.lock();
+        assert!(encrypted.history(&HistoryQuery::default()).is_err());
+
+        drop(encrypted);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_key_parts_are_isolated() {
+        let path = temp_db();
+        let cache = NeutralizationCache::new(Some(path.clone())).unwrap();
+        let phi = key("phi3:mini", "adult");
+        cache.set("SCARY NEWS!!!", &phi, "Scary news.", &[], 4, &ContentSource::default()).unwrap();
+
+        assert!(cache.get("SCARY NEWS!!!", &phi).is_some());
+        assert!(cache.get("SCARY NEWS!!!", &key("llama3:8b", "adult")).is_none());
+        assert!(cache.get("SCARY NEWS!!!", &key("phi3:mini", "child")).is_none());
+
+        assert_eq!(cache.invalidate(&CacheInvalidation::Model("phi3:mini".into())).unwrap(), 1);
+        assert!(cache.get("SCARY NEWS!!!", &phi).is_none());
+
+        drop(cache);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_migrates_legacy_table() {
+        let path = temp_db();
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(
+                r#"
+                CREATE TABLE neutralization_cache (
+                    content_hash TEXT PRIMARY KEY,
+                    original TEXT NOT NULL,
+                    neutralized TEXT NOT NULL,
+                    techniques TEXT NOT NULL,
+                    severity INTEGER NOT NULL,
+                    created_at INTEGER NOT NULL,
+                    hit_count INTEGER DEFAULT 0
+                );
+                INSERT INTO neutralization_cache VALUES ('abc', 'A', 'a', '[]', 1, 0, 0);
+                "#,
+            )
+            .unwrap();
+        }
+
+        let cache = NeutralizationCache::new(Some(path.clone())).unwrap();
+        let conn = cache.conn.lock().unwrap();
+        let model: String = conn
+            .query_row("SELECT model FROM neutralization_cache WHERE content_hash = 'abc'", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(model, "");
+        let version: i64 = conn.query_row("PRAGMA user_version", [], |r| r.get(0)).unwrap();
+        assert_eq!(version, SCHEMA_VERSION);
+        drop(conn);
+        drop(cache);
+
+        // The unversioned file was backed up untouched
+        let backup = backup_path(&path, 0);
+        let conn = Connection::open(&backup).unwrap();
+        assert!(table_columns(&conn, "neutralization_cache").unwrap().iter().all(|c| c != "cache_key"));
+        drop(conn);
+
+        let _ = std::fs::remove_file(path);
+        let _ = std::fs::remove_file(backup);
+    }
+
+    #[test]
+    fn test_migrations_are_ordered_and_idempotent() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        for (index, migration) in MIGRATIONS.iter().enumerate() {
+            migration(&conn).unwrap();
+            conn.pragma_update(None, "user_version", index as i64 + 1).unwrap();
+        }
+        // Re-running on an up-to-date database is a no-op
+        migrate(&mut conn, None).unwrap();
+        // Each migration also tolerates the tables it creates already existing
+        for migration in MIGRATIONS {
+            migration(&conn).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_rebuilds_corrupt_database() {
+        let path = temp_db();
+        std::fs::write(&path, b"this is not a sqlite database, just bytes").unwrap();
+
+        let cache = NeutralizationCache::new(Some(path.clone())).unwrap();
+        cache.set("Hello", &key("phi3:mini", "adult"), "Hello", &[], 0, &ContentSource::default()).unwrap();
+        assert!(cache.get("Hello", &key("phi3:mini", "adult")).is_some());
+        drop(cache);
+
+        let mut broken = path.as_os_str().to_owned();
+        broken.push(".broken");
+        assert!(Path::new(&broken).exists());
 
-    pub fn is_empty(&self) -> bool {
-        self.map.is_empty()
+        let _ = std::fs::remove_file(path);
+        let _ = std::fs::remove_file(PathBuf::from(broken));
     }
 }
@@ -0,0 +1,236 @@
+//! At-rest Encryption Module
+//!
+//! A random data key encrypts sensitive cache columns with AES-256-GCM. The
+//! data key is stored in a keyfile, wrapped by a key derived from the
+//! parental passphrase with Argon2id, so the same file works on any OS.
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use argon2::{Algorithm, Argon2, Params, Version};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use zeroize::{Zeroize, Zeroizing};
+
+/// Prefix marking an encrypted column value
+const FIELD_PREFIX: &str = "enc1:";
+const KEYFILE_VERSION: u32 = 1;
+const NONCE_LEN: usize = 12;
+const SALT_LEN: usize = 16;
+
+/// Key that encrypts cache contents; wiped from memory on drop
+pub struct DataKey(Zeroizing<[u8; 32]>);
+
+impl DataKey {
+    pub fn generate() -> Self {
+        let mut key = Zeroizing::new([0u8; 32]);
+        OsRng.fill_bytes(key.as_mut());
+        Self(key)
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(self.0.as_ref()))
+    }
+}
+
+/// On-disk keyfile holding the passphrase-wrapped data key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Keyfile {
+    pub version: u32,
+    pub kdf: String,
+    pub salt: String,
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+    pub nonce: String,
+    pub wrapped_key: String,
+}
+
+impl Keyfile {
+    /// Wrap `key` with a key derived from `passphrase`
+    pub fn wrap(key: &DataKey, passphrase: &str) -> Result<Self, String> {
+        if passphrase.chars().count() < 8 {
+            return Err("Passphrase must be at least 8 characters".to_string());
+        }
+
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let params = Params::default();
+
+        let kek = derive_key(passphrase, &salt, &params)?;
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let wrapped = kek
+            .cipher()
+            .encrypt(&nonce, key.0.as_ref())
+            .map_err(|_| "Failed to wrap encryption key".to_string())?;
+
+        Ok(Self {
+            version: KEYFILE_VERSION,
+            kdf: "argon2id".to_string(),
+            salt: hex::encode(salt),
+            memory_kib: params.m_cost(),
+            iterations: params.t_cost(),
+            parallelism: params.p_cost(),
+            nonce: hex::encode(nonce),
+            wrapped_key: hex::encode(wrapped),
+        })
+    }
+
+    /// Recover the data key; fails on a wrong passphrase
+    pub fn unwrap_key(&self, passphrase: &str) -> Result<DataKey, String> {
+        if self.version != KEYFILE_VERSION || self.kdf != "argon2id" {
+            return Err(format!("Unsupported keyfile version {}", self.version));
+        }
+
+        let salt = hex::decode(&self.salt).map_err(|_| "Corrupt keyfile salt")?;
+        let nonce = hex::decode(&self.nonce).map_err(|_| "Corrupt keyfile nonce")?;
+        let wrapped = hex::decode(&self.wrapped_key).map_err(|_| "Corrupt keyfile key")?;
+        if nonce.len() != NONCE_LEN {
+            return Err("Corrupt keyfile nonce".to_string());
+        }
+
+        let params = Params::new(self.memory_kib, self.iterations, self.parallelism, Some(32))
+            .map_err(|e| format!("Invalid keyfile parameters: {}", e))?;
+        let kek = derive_key(passphrase, &salt, &params)?;
+
+        let mut plain = kek
+            .cipher()
+            .decrypt(Nonce::from_slice(&nonce), wrapped.as_ref())
+            .map_err(|_| "Incorrect passphrase".to_string())?;
+        if plain.len() != 32 {
+            plain.zeroize();
+            return Err("Corrupt keyfile key".to_string());
+        }
+
+        let mut key = Zeroizing::new([0u8; 32]);
+        key.copy_from_slice(&plain);
+        plain.zeroize();
+        Ok(DataKey(key))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read keyfile: {}", e))?;
+        serde_json::from_str(&contents).map_err(|e| format!("Failed to parse keyfile: {}", e))
+    }
+
+    /// Write the keyfile atomically, replacing any existing one
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize keyfile: {}", e))?;
+
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        let tmp = std::path::PathBuf::from(tmp);
+
+        let mut file = fs::File::create(&tmp)
+            .map_err(|e| format!("Failed to write keyfile: {}", e))?;
+        file.write_all(json.as_bytes())
+            .and_then(|_| file.sync_all())
+            .map_err(|e| format!("Failed to write keyfile: {}", e))?;
+        drop(file);
+
+        fs::rename(&tmp, path).map_err(|e| format!("Failed to write keyfile: {}", e))
+    }
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], params: &Params) -> Result<DataKey, String> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params.clone())
+        .hash_password_into(passphrase.as_bytes(), salt, key.as_mut())
+        .map_err(|e| format!("Failed to derive key: {}", e))?;
+    Ok(DataKey(key))
+}
+
+/// Whether a column value was written by [`encrypt_field`]
+pub fn is_encrypted(value: &str) -> bool {
+    value.starts_with(FIELD_PREFIX)
+}
+
+/// Encrypt a column value as `enc1:<hex nonce + ciphertext>`
+pub fn encrypt_field(key: &DataKey, plaintext: &str) -> Result<String, String> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = key
+        .cipher()
+        .encrypt(&nonce, plaintext.as_bytes())
+        .map_err(|_| "Failed to encrypt cache entry".to_string())?;
+
+    let mut bytes = nonce.to_vec();
+    bytes.extend_from_slice(&ciphertext);
+    Ok(format!("{}{}", FIELD_PREFIX, hex::encode(bytes)))
+}
+
+/// Decrypt a column value; plaintext values are returned unchanged
+pub fn decrypt_field(key: &DataKey, value: &str) -> Result<String, String> {
+    let Some(encoded) = value.strip_prefix(FIELD_PREFIX) else {
+        return Ok(value.to_string());
+    };
+
+    let bytes = hex::decode(encoded).map_err(|_| "Corrupt encrypted cache entry")?;
+    if bytes.len() < NONCE_LEN {
+        return Err("Corrupt encrypted cache entry".to_string());
+    }
+    let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+
+    let plain = key
+        .cipher()
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "Cache entry was encrypted with a different key".to_string())?;
+    String::from_utf8(plain).map_err(|_| "Corrupt encrypted cache entry".to_string())
+}
+
+/// Overwrite a file with zeros before removing it. Best effort: journaling
+/// and copy-on-write filesystems or SSDs may keep older copies.
+pub fn wipe_file(path: &Path) -> Result<(), String> {
+    let Ok(metadata) = fs::metadata(path) else {
+        return Ok(());
+    };
+
+    if let Ok(mut file) = fs::OpenOptions::new().write(true).open(path) {
+        let zeros = vec![0u8; 64 * 1024];
+        let mut remaining = metadata.len();
+        while remaining > 0 {
+            let chunk = remaining.min(zeros.len() as u64) as usize;
+            if file.write_all(&zeros[..chunk]).is_err() {
+                break;
+            }
+            remaining -= chunk as u64;
+        }
+        let _ = file.sync_all();
+    }
+
+    fs::remove_file(path).map_err(|e| format!("Failed to remove {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_and_unwrap_key() {
+        let key = DataKey::generate();
+        let keyfile = Keyfile::wrap(&key, "correct horse battery").unwrap();
+
+        let unwrapped = keyfile.unwrap_key("correct horse battery").unwrap();
+        assert_eq!(unwrapped.0.as_ref(), key.0.as_ref());
+        assert_eq!(
+            keyfile.unwrap_key("wrong passphrase").err().as_deref(),
+            Some("Incorrect passphrase")
+        );
+        assert!(Keyfile::wrap(&key, "short").is_err());
+    }
+
+    #[test]
+    fn test_field_round_trip() {
+        let key = DataKey::generate();
+        let encrypted = encrypt_field(&key, "They are LYING to you!!!").unwrap();
+
+        assert!(is_encrypted(&encrypted));
+        assert!(!encrypted.contains("LYING"));
+        assert_eq!(decrypt_field(&key, &encrypted).unwrap(), "They are LYING to you!!!");
+        assert_eq!(decrypt_field(&key, "plain text").unwrap(), "plain text");
+        assert!(decrypt_field(&DataKey::generate(), &encrypted).is_err());
+    }
+}
@@ -6,15 +6,24 @@
 use axum::{
     extract::State,
     http::StatusCode,
+    response::sse::{Event, KeepAlive, Sse},
     response::Json,
-    routing::get,
+    routing::{get, post},
     Router,
 };
-use serde::Serialize;
+use futures_util::stream::{self, Stream};
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::{broadcast, Mutex};
 use tower_http::cors::{Any, CorsLayer};
 
+use crate::actions::ActionDecision;
+use crate::governor::{GovernorMode, ResourceGovernor};
+use crate::profiles::Profile;
+use crate::schedule::{self, EffectivePolicy};
+use crate::settings::AppSettings;
 use crate::supervisor::{FriendlyStatus, OllamaSupervisor};
 
 /// Bridge server port - the extension will check this fixed port
@@ -40,22 +49,112 @@ pub struct BridgeStatus {
 
     /// Does the user need to complete setup
     pub needs_setup: bool,
+
+    /// Resource governor mode; the extension should back off unless "normal"
+    pub governor_mode: GovernorMode,
+
+    /// Bumped on every settings change; refetch anything derived from settings when it moves
+    pub settings_revision: u64,
+
+    /// Profile protection currently follows: persona, threshold and platforms
+    pub profile: Profile,
+
+    /// The profile with its schedule applied; skip content while `paused`
+    pub policy: EffectivePolicy,
+}
+
+/// Result the extension neutralized itself, sent to `/decide`
+#[derive(Debug, Deserialize)]
+pub struct DecideRequest {
+    pub techniques: Vec<String>,
+    pub severity: i32,
+    /// Site the content came from, e.g. "twitter"; empty if unknown
+    #[serde(default)]
+    pub platform: String,
+}
+
+/// Pushed to the extension over `/events`
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BridgeEvent {
+    /// Settings changed in the app or on disk
+    SettingsChanged { revision: u64 },
+    /// A profile switch or schedule window changed the effective policy
+    PolicyChanged { policy: EffectivePolicy },
+}
+
+impl BridgeEvent {
+    /// SSE event name
+    fn name(&self) -> &'static str {
+        match self {
+            BridgeEvent::SettingsChanged { .. } => "settings-changed",
+            BridgeEvent::PolicyChanged { .. } => "policy-changed",
+        }
+    }
+}
+
+/// Fan-out of app events to connected extensions
+pub struct BridgeEvents {
+    sender: broadcast::Sender<BridgeEvent>,
+    settings_revision: AtomicU64,
+}
+
+impl BridgeEvents {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(16);
+        Self {
+            sender,
+            settings_revision: AtomicU64::new(0),
+        }
+    }
+
+    pub fn settings_revision(&self) -> u64 {
+        self.settings_revision.load(Ordering::SeqCst)
+    }
+
+    /// Tell connected extensions that settings changed
+    pub fn settings_changed(&self) {
+        let revision = self.settings_revision.fetch_add(1, Ordering::SeqCst) + 1;
+        // No receivers just means no extension is listening
+        let _ = self.sender.send(BridgeEvent::SettingsChanged { revision });
+    }
+
+    /// Tell connected extensions what protection follows now
+    pub fn policy_changed(&self, policy: EffectivePolicy) {
+        let _ = self.sender.send(BridgeEvent::PolicyChanged { policy });
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<BridgeEvent> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for BridgeEvents {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// Shared state for the bridge server
 pub struct BridgeState {
-    pub supervisor: Arc<Mutex<OllamaSupervisor>>,
-    pub first_run_complete: Arc<Mutex<bool>>,
+    pub supervisor: Arc<OllamaSupervisor>,
+    pub governor: Arc<ResourceGovernor>,
+    pub settings: Arc<Mutex<AppSettings>>,
+    pub events: Arc<BridgeEvents>,
 }
 
 /// Start the extension bridge HTTP server
 pub async fn start_bridge_server(
-    supervisor: Arc<Mutex<OllamaSupervisor>>,
-    first_run_complete: Arc<Mutex<bool>>,
+    supervisor: Arc<OllamaSupervisor>,
+    governor: Arc<ResourceGovernor>,
+    settings: Arc<Mutex<AppSettings>>,
+    events: Arc<BridgeEvents>,
 ) {
     let state = Arc::new(BridgeState {
         supervisor,
-        first_run_complete,
+        governor,
+        settings,
+        events,
     });
 
     // Configure CORS to allow extension access
@@ -67,6 +166,8 @@ pub async fn start_bridge_server(
     let app = Router::new()
         .route("/status", get(status_handler))
         .route("/health", get(health_handler))
+        .route("/events", get(events_handler))
+        .route("/decide", post(decide_handler))
         .layer(cors)
         .with_state(state);
 
@@ -93,25 +194,30 @@ async fn health_handler() -> &'static str {
 
 /// Status endpoint - full app status for extension
 async fn status_handler(State(state): State<Arc<BridgeState>>) -> Result<Json<BridgeStatus>, StatusCode> {
-    let supervisor = state.supervisor.lock().await;
-    let friendly_status = supervisor.get_friendly_status().await;
-    let is_healthy = supervisor.is_healthy().await;
-    drop(supervisor);
+    let friendly_status = state.supervisor.get_friendly_status().await;
+    let is_healthy = state.supervisor.is_healthy().await;
 
-    let first_run = *state.first_run_complete.lock().await;
+    let (first_run, profile) = {
+        let settings = state.settings.lock().await;
+        (settings.first_run_complete, settings.active_profile())
+    };
 
     let status_message = match &friendly_status {
         FriendlyStatus::Running { message } => message.clone(),
         FriendlyStatus::Starting { message } => message.clone(),
         FriendlyStatus::NotInstalled { message, .. } => message.clone(),
         FriendlyStatus::ModelMissing { message } => message.clone(),
+        FriendlyStatus::ModelChanged { message } => message.clone(),
+        FriendlyStatus::Degraded { message } => message.clone(),
         FriendlyStatus::Error { message } => message.clone(),
         FriendlyStatus::Stopped { message } => message.clone(),
     };
 
     let needs_setup = matches!(
         friendly_status,
-        FriendlyStatus::NotInstalled { .. } | FriendlyStatus::ModelMissing { .. }
+        FriendlyStatus::NotInstalled { .. }
+            | FriendlyStatus::ModelMissing { .. }
+            | FriendlyStatus::ModelChanged { .. }
     ) || !first_run;
 
     Ok(Json(BridgeStatus {
@@ -121,12 +227,49 @@ async fn status_handler(State(state): State<Arc<BridgeState>>) -> Result<Json<Br
         version: env!("CARGO_PKG_VERSION").to_string(),
         ollama_ready: is_healthy,
         needs_setup,
+        governor_mode: state.governor.mode(),
+        settings_revision: state.events.settings_revision(),
+        profile,
+        policy: state.supervisor.effective_policy(),
     }))
 }
 
+/// What to do with a result, decided by the same rules as the app's own
+/// neutralizations
+async fn decide_handler(
+    State(state): State<Arc<BridgeState>>,
+    Json(request): Json<DecideRequest>,
+) -> Json<ActionDecision> {
+    let profile = state.settings.lock().await.active_profile();
+    let policy = schedule::evaluate(&profile, chrono::Local::now());
+    Json(policy.decide(&request.techniques, request.severity, &request.platform))
+}
+
+/// Server-sent events for the extension, e.g. `settings-changed`
+async fn events_handler(
+    State(state): State<Arc<BridgeState>>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let events = stream::unfold(state.events.subscribe(), |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => {
+                    let data = serde_json::to_string(&event).unwrap_or_default();
+                    return Some((Ok(Event::default().event(event.name()).data(data)), receiver));
+                }
+                // A slow client only needs the latest state; it refetches /status
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::schedule::evaluate;
 
     #[test]
     fn test_bridge_status_serialization() {
@@ -137,10 +280,30 @@ mod tests {
             version: "1.0.0".to_string(),
             ollama_ready: true,
             needs_setup: false,
+            governor_mode: GovernorMode::Throttled,
+            settings_revision: 3,
+            profile: Profile::for_persona("kid", "Ana", "child"),
+            policy: evaluate(&Profile::for_persona("kid", "Ana", "child"), chrono::Local::now()),
         };
 
         let json = serde_json::to_string(&status).unwrap();
         assert!(json.contains("Protected"));
         assert!(json.contains("11434"));
+        assert!(json.contains("\"governor_mode\":\"throttled\""));
+        assert!(json.contains("\"settings_revision\":3"));
+        assert!(json.contains("\"persona\":\"child\""));
+        assert!(json.contains("\"paused\":false"));
+    }
+
+    #[test]
+    fn test_settings_changed_reaches_subscribers() {
+        let events = BridgeEvents::new();
+        let mut receiver = events.subscribe();
+        events.settings_changed();
+
+        assert_eq!(events.settings_revision(), 1);
+        let event = receiver.try_recv().unwrap();
+        assert_eq!(event.name(), "settings-changed");
+        assert_eq!(serde_json::to_string(&event).unwrap(), r#"{"type":"settings_changed","revision":1}"#);
     }
 }
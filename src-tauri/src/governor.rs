@@ -0,0 +1,459 @@
+//! Resource Governor Module
+//!
+//! Watches CPU load, available RAM and battery state so neutralization backs
+//! off while the machine is busy (e.g. gaming) or running low on battery.
+
+use serde::{Deserialize, Serialize};
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System};
+use tokio::time::{sleep, Duration};
+
+use crate::supervisor::OllamaSupervisor;
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// How neutralization should behave right now, from least to most restrictive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GovernorMode {
+    /// Full local AI neutralization
+    Normal,
+    /// AI neutralization with an added delay between requests
+    Throttled,
+    /// Skip the model and use the rule-based neutralizer
+    RuleBased,
+    /// Don't neutralize at all
+    Paused,
+}
+
+/// Thresholds and actions for the governor (stored in `AppSettings`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResourcePolicy {
+    /// Turn the governor off entirely
+    pub enabled: bool,
+    /// CPU usage by other programs above which the machine counts as busy
+    pub busy_cpu_percent: f32,
+    /// Available RAM below which the machine counts as busy
+    pub min_available_ram_mb: u64,
+    /// What to do while the machine is busy
+    pub busy_action: GovernorMode,
+    /// What to do while running on battery
+    pub on_battery_action: GovernorMode,
+    /// Battery level at or below which `low_battery_action` applies
+    pub low_battery_percent: u8,
+    /// What to do while the battery is low
+    pub low_battery_action: GovernorMode,
+    /// Unload the model from memory while AI neutralization is off
+    pub unload_model: bool,
+    /// Delay added before each request in throttled mode
+    pub throttle_delay_ms: u64,
+    /// Seconds between resource samples
+    pub check_interval_secs: u64,
+    /// Consecutive samples required before switching mode
+    pub sustained_samples: u32,
+}
+
+impl Default for ResourcePolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            busy_cpu_percent: 85.0,
+            min_available_ram_mb: 1024,
+            busy_action: GovernorMode::RuleBased,
+            on_battery_action: GovernorMode::Throttled,
+            low_battery_percent: 20,
+            low_battery_action: GovernorMode::RuleBased,
+            unload_model: true,
+            throttle_delay_ms: 1500,
+            check_interval_secs: 10,
+            sustained_samples: 3,
+        }
+    }
+}
+
+/// Battery charge and power source
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BatteryState {
+    pub percent: u8,
+    pub on_battery: bool,
+}
+
+/// One sample of system resources
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResourceSnapshot {
+    /// CPU usage of everything except Ollama, 0-100
+    pub cpu_percent: f32,
+    pub available_ram_mb: u64,
+    pub battery: Option<BatteryState>,
+}
+
+/// Current governor decision, reported to the UI and the extension
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GovernorStatus {
+    pub mode: GovernorMode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    pub snapshot: ResourceSnapshot,
+    pub model_unloaded: bool,
+    pub updated_at: i64,
+}
+
+/// Callback invoked whenever the governor changes mode
+pub type GovernorListener = Arc<dyn Fn(GovernorStatus) + Send + Sync>;
+
+/// Decide the mode for a snapshot, picking the most restrictive trigger
+pub fn evaluate(policy: &ResourcePolicy, snapshot: &ResourceSnapshot) -> (GovernorMode, Option<String>) {
+    if !policy.enabled {
+        return (GovernorMode::Normal, None);
+    }
+
+    let mut triggers: Vec<(GovernorMode, String)> = Vec::new();
+
+    if snapshot.cpu_percent >= policy.busy_cpu_percent {
+        triggers.push((
+            policy.busy_action,
+            format!("CPU busy ({:.0}%)", snapshot.cpu_percent),
+        ));
+    }
+
+    if snapshot.available_ram_mb < policy.min_available_ram_mb {
+        triggers.push((
+            policy.busy_action,
+            format!("Low memory ({} MB free)", snapshot.available_ram_mb),
+        ));
+    }
+
+    if let Some(battery) = snapshot.battery.filter(|b| b.on_battery) {
+        if battery.percent <= policy.low_battery_percent {
+            triggers.push((
+                policy.low_battery_action,
+                format!("Low battery ({}%)", battery.percent),
+            ));
+        } else {
+            triggers.push((policy.on_battery_action, "On battery".to_string()));
+        }
+    }
+
+    triggers
+        .into_iter()
+        .max_by_key(|(mode, _)| *mode)
+        .map(|(mode, reason)| (mode, Some(reason)))
+        .unwrap_or((GovernorMode::Normal, None))
+}
+
+/// Resource governor - samples the machine and publishes a `GovernorMode`
+pub struct ResourceGovernor {
+    policy: Mutex<ResourcePolicy>,
+    status: Mutex<GovernorStatus>,
+    listener: Mutex<Option<GovernorListener>>,
+    is_monitoring: AtomicBool,
+}
+
+impl ResourceGovernor {
+    pub fn new(policy: ResourcePolicy) -> Self {
+        Self {
+            policy: Mutex::new(policy),
+            status: Mutex::new(GovernorStatus {
+                mode: GovernorMode::Normal,
+                reason: None,
+                snapshot: ResourceSnapshot::default(),
+                model_unloaded: false,
+                updated_at: now_secs(),
+            }),
+            listener: Mutex::new(None),
+            is_monitoring: AtomicBool::new(false),
+        }
+    }
+
+    /// Current policy
+    pub fn policy(&self) -> ResourcePolicy {
+        self.policy.lock().unwrap().clone()
+    }
+
+    /// Replace the policy (e.g. after settings change)
+    pub fn set_policy(&self, policy: ResourcePolicy) {
+        *self.policy.lock().unwrap() = policy;
+    }
+
+    /// Latest governor decision
+    pub fn status(&self) -> GovernorStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    /// Current mode, as used by the neutralization path
+    pub fn mode(&self) -> GovernorMode {
+        self.status.lock().unwrap().mode
+    }
+
+    /// Register the callback that receives mode changes
+    pub fn set_listener(&self, listener: GovernorListener) {
+        *self.listener.lock().unwrap() = Some(listener);
+    }
+
+    /// Start sampling resources and applying the policy
+    pub fn start_monitor(self: Arc<Self>, supervisor: Arc<OllamaSupervisor>) {
+        if self.is_monitoring.swap(true, Ordering::SeqCst) {
+            log::info!("Resource governor already running");
+            return;
+        }
+
+        tokio::spawn(async move {
+            log::info!("Starting resource governor");
+            let mut sys = System::new();
+            let mut pending: Option<(GovernorMode, u32)> = None;
+
+            loop {
+                let policy = self.policy();
+                let snapshot = sample(&mut sys).await;
+                let (mode, reason) = evaluate(&policy, &snapshot);
+                let current = self.mode();
+
+                // Require the new mode to hold for a few samples to avoid flapping
+                let confirmed = if mode == current {
+                    pending = None;
+                    false
+                } else {
+                    let count = match pending {
+                        Some((pending_mode, count)) if pending_mode == mode => count + 1,
+                        _ => 1,
+                    };
+                    pending = Some((mode, count));
+                    count >= policy.sustained_samples.max(1)
+                };
+
+                if confirmed {
+                    pending = None;
+                    self.apply(mode, reason, snapshot, &policy, &supervisor).await;
+                } else {
+                    self.status.lock().unwrap().snapshot = snapshot;
+                }
+
+                sleep(Duration::from_secs(policy.check_interval_secs.max(1))).await;
+            }
+        });
+    }
+
+    /// Switch to a new mode and carry out its side effects
+    async fn apply(
+        &self,
+        mode: GovernorMode,
+        reason: Option<String>,
+        snapshot: ResourceSnapshot,
+        policy: &ResourcePolicy,
+        supervisor: &OllamaSupervisor,
+    ) {
+        log::info!(
+            "Resource governor: {:?} ({})",
+            mode,
+            reason.as_deref().unwrap_or("resources available")
+        );
+
+        let ai_off = mode >= GovernorMode::RuleBased;
+        supervisor.set_inference_suspended(ai_off);
+
+        let mut model_unloaded = self.status.lock().unwrap().model_unloaded;
+        if ai_off && policy.unload_model && !model_unloaded {
+            match supervisor.unload_model().await {
+                Ok(()) => model_unloaded = true,
+                Err(e) => log::warn!("Failed to unload model: {}", e),
+            }
+        } else if !ai_off {
+            // Ollama reloads the model on the next request
+            model_unloaded = false;
+        }
+
+        let status = GovernorStatus {
+            mode,
+            reason,
+            snapshot,
+            model_unloaded,
+            updated_at: now_secs(),
+        };
+        *self.status.lock().unwrap() = status.clone();
+
+        let listener = self.listener.lock().unwrap().clone();
+        if let Some(listener) = listener {
+            listener(status);
+        }
+    }
+}
+
+/// Take a resource sample; CPU usage of Ollama itself is excluded
+async fn sample(sys: &mut System) -> ResourceSnapshot {
+    sys.refresh_cpu_usage();
+    sys.refresh_processes_specifics(ProcessesToUpdate::All, true, ProcessRefreshKind::new().with_cpu());
+    sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL).await;
+    sys.refresh_cpu_usage();
+    sys.refresh_processes_specifics(ProcessesToUpdate::All, true, ProcessRefreshKind::new().with_cpu());
+    sys.refresh_memory();
+
+    let cpu_count = sys.cpus().len().max(1) as f32;
+    let ollama_cpu: f32 = sys
+        .processes()
+        .values()
+        .filter(|p| p.name().to_string_lossy().to_lowercase().starts_with("ollama"))
+        .map(|p| p.cpu_usage())
+        .sum::<f32>()
+        / cpu_count;
+
+    ResourceSnapshot {
+        cpu_percent: (sys.global_cpu_usage() - ollama_cpu).clamp(0.0, 100.0),
+        available_ram_mb: sys.available_memory() / 1024 / 1024,
+        battery: tokio::task::spawn_blocking(read_battery).await.ok().flatten(),
+    }
+}
+
+fn now_secs() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Read battery state from sysfs
+#[cfg(target_os = "linux")]
+fn read_battery() -> Option<BatteryState> {
+    let entries = std::fs::read_dir("/sys/class/power_supply").ok()?;
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let kind = std::fs::read_to_string(path.join("type")).unwrap_or_default();
+        if kind.trim() != "Battery" {
+            continue;
+        }
+
+        let percent = std::fs::read_to_string(path.join("capacity"))
+            .ok()?
+            .trim()
+            .parse::<u8>()
+            .ok()?;
+        let status = std::fs::read_to_string(path.join("status")).unwrap_or_default();
+
+        return Some(BatteryState {
+            percent,
+            on_battery: status.trim() == "Discharging",
+        });
+    }
+
+    None
+}
+
+/// Read battery state from `pmset`
+#[cfg(target_os = "macos")]
+fn read_battery() -> Option<BatteryState> {
+    let output = Command::new("pmset").args(["-g", "batt"]).output().ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    // e.g. "Now drawing from 'Battery Power'\n -InternalBattery-0 (id=...) 85%; discharging; ..."
+    let on_battery = stdout.contains("'Battery Power'");
+    let percent = stdout
+        .split_whitespace()
+        .find_map(|word| word.trim_end_matches(';').strip_suffix('%'))?
+        .parse::<u8>()
+        .ok()?;
+
+    Some(BatteryState { percent, on_battery })
+}
+
+/// Read battery state from WMI via PowerShell
+#[cfg(target_os = "windows")]
+fn read_battery() -> Option<BatteryState> {
+    let mut cmd = Command::new("powershell");
+    cmd.args([
+        "-NoProfile",
+        "-Command",
+        "$b = Get-CimInstance Win32_Battery | Select-Object -First 1; if ($b) { \"$($b.EstimatedChargeRemaining),$($b.BatteryStatus)\" }",
+    ]);
+    cmd.creation_flags(CREATE_NO_WINDOW);
+
+    let output = cmd.output().ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (percent, status) = stdout.trim().split_once(',')?;
+
+    // BatteryStatus 1 is discharging, 4 low and 5 critical; the rest
+    // (AC, fully charged, charging, unknown) mean the machine is plugged in
+    Some(BatteryState {
+        percent: percent.trim().parse().ok()?,
+        on_battery: matches!(status.trim(), "1" | "4" | "5"),
+    })
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn read_battery() -> Option<BatteryState> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn idle() -> ResourceSnapshot {
+        ResourceSnapshot {
+            cpu_percent: 10.0,
+            available_ram_mb: 8192,
+            battery: None,
+        }
+    }
+
+    #[test]
+    fn test_idle_machine_is_normal() {
+        let (mode, reason) = evaluate(&ResourcePolicy::default(), &idle());
+        assert_eq!(mode, GovernorMode::Normal);
+        assert!(reason.is_none());
+    }
+
+    #[test]
+    fn test_most_restrictive_trigger_wins() {
+        let policy = ResourcePolicy {
+            low_battery_action: GovernorMode::Paused,
+            ..ResourcePolicy::default()
+        };
+        let snapshot = ResourceSnapshot {
+            cpu_percent: 95.0,
+            battery: Some(BatteryState {
+                percent: 10,
+                on_battery: true,
+            }),
+            ..idle()
+        };
+
+        let (mode, reason) = evaluate(&policy, &snapshot);
+        assert_eq!(mode, GovernorMode::Paused);
+        assert!(reason.unwrap().contains("battery"));
+    }
+
+    #[test]
+    fn test_charging_battery_is_ignored() {
+        let snapshot = ResourceSnapshot {
+            battery: Some(BatteryState {
+                percent: 5,
+                on_battery: false,
+            }),
+            ..idle()
+        };
+        assert_eq!(evaluate(&ResourcePolicy::default(), &snapshot).0, GovernorMode::Normal);
+    }
+
+    #[test]
+    fn test_disabled_policy_never_throttles() {
+        let policy = ResourcePolicy {
+            enabled: false,
+            ..ResourcePolicy::default()
+        };
+        let snapshot = ResourceSnapshot {
+            cpu_percent: 100.0,
+            available_ram_mb: 10,
+            ..idle()
+        };
+        assert_eq!(evaluate(&policy, &snapshot).0, GovernorMode::Normal);
+    }
+}
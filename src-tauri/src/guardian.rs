@@ -0,0 +1,451 @@
+//! Guardian Lock Module
+//!
+//! Keeps anyone without the guardian PIN from turning protection off. The PIN
+//! is stored only as a salted Argon2id hash; unlocking hands out a token that
+//! privileged commands must present and that expires after a few minutes.
+//! A guardian file that can't be read, or that is gone while settings say a
+//! PIN was set, fails closed: everything stays locked until the PIN is
+//! reset, and the reset is recorded for the guardian to see.
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::OsRng;
+use argon2::{Algorithm, Argon2, Params, Version};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use zeroize::Zeroizing;
+
+const GUARDIAN_FILE_VERSION: u32 = 1;
+const MIN_PIN_LENGTH: usize = 4;
+const SALT_LEN: usize = 16;
+const HASH_LEN: usize = 32;
+/// How long an unlock token stays valid
+pub const UNLOCK_TTL: Duration = Duration::from_secs(10 * 60);
+/// Failed attempts in a row before unlocking is paused
+const MAX_FAILED_ATTEMPTS: u32 = 5;
+const LOCKOUT: Duration = Duration::from_secs(60);
+/// Failed attempt timestamps kept for the guardian to review
+const FAILED_ATTEMPTS_KEPT: usize = 50;
+const DAMAGED: &str = "The guardian file is damaged. Reset the guardian PIN to continue";
+
+/// On-disk guardian file with the hashed PIN and the failed attempt log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct GuardianFile {
+    version: u32,
+    kdf: String,
+    salt: String,
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+    pin_hash: String,
+    /// Unix timestamps of failed unlock attempts, oldest first
+    #[serde(default)]
+    failed_attempts: Vec<i64>,
+    /// Unix timestamp of the last reset after the file was found damaged
+    #[serde(default)]
+    reset_at: Option<i64>,
+}
+
+impl GuardianFile {
+    fn new(pin: &str) -> Result<Self, String> {
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let params = Params::default();
+        let hash = hash_pin(pin, &salt, &params)?;
+
+        Ok(Self {
+            version: GUARDIAN_FILE_VERSION,
+            kdf: "argon2id".to_string(),
+            salt: hex::encode(salt),
+            memory_kib: params.m_cost(),
+            iterations: params.t_cost(),
+            parallelism: params.p_cost(),
+            pin_hash: hex::encode(hash.as_ref()),
+            failed_attempts: Vec::new(),
+            reset_at: None,
+        })
+    }
+
+    fn verify(&self, pin: &str) -> Result<bool, String> {
+        if self.version != GUARDIAN_FILE_VERSION || self.kdf != "argon2id" {
+            return Err(format!("Unsupported guardian file version {}", self.version));
+        }
+
+        let salt = hex::decode(&self.salt).map_err(|_| "Corrupt guardian file salt")?;
+        let expected = hex::decode(&self.pin_hash).map_err(|_| "Corrupt guardian file hash")?;
+        let params = Params::new(self.memory_kib, self.iterations, self.parallelism, Some(HASH_LEN))
+            .map_err(|e| format!("Invalid guardian file parameters: {}", e))?;
+        let actual = hash_pin(pin, &salt, &params)?;
+
+        Ok(constant_time_eq(actual.as_ref(), &expected))
+    }
+
+    fn load(path: &Path) -> Result<Option<Self>, String> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read guardian file: {}", e))?;
+        serde_json::from_str(&contents)
+            .map(Some)
+            .map_err(|e| format!("Failed to parse guardian file: {}", e))
+    }
+
+    fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create config directory: {}", e))?;
+        }
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize guardian file: {}", e))?;
+        crate::settings::write_atomically(path, json.as_bytes())
+            .map_err(|e| format!("Failed to write guardian file: {}", e))
+    }
+}
+
+fn hash_pin(pin: &str, salt: &[u8], params: &Params) -> Result<Zeroizing<[u8; HASH_LEN]>, String> {
+    let mut hash = Zeroizing::new([0u8; HASH_LEN]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params.clone())
+        .hash_password_into(pin.as_bytes(), salt, hash.as_mut())
+        .map_err(|e| format!("Failed to hash PIN: {}", e))?;
+    Ok(hash)
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Guardian lock state reported to the UI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuardianStatus {
+    /// Whether a PIN is set
+    pub enabled: bool,
+    /// The guardian file couldn't be read; everything stays locked until
+    /// the PIN is reset
+    pub damaged: bool,
+    /// Unix timestamp of the last reset of a damaged guardian file
+    pub reset_at: Option<i64>,
+    /// Seconds until unlocking is allowed again after too many failures
+    pub locked_out_secs: u64,
+    /// Unix timestamps of recent failed unlock attempts, oldest first
+    pub failed_attempts: Vec<i64>,
+}
+
+/// Token returned by a successful unlock
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnlockToken {
+    pub token: String,
+    /// Unix timestamp after which the token is rejected
+    pub expires_at: i64,
+}
+
+#[derive(Default)]
+struct GuardianState {
+    file: Option<GuardianFile>,
+    damaged: bool,
+    tokens: HashMap<String, Instant>,
+    consecutive_failures: u32,
+    locked_until: Option<Instant>,
+}
+
+/// Guardian PIN and the unlock tokens handed out for it
+pub struct Guardian {
+    path: PathBuf,
+    state: Mutex<GuardianState>,
+}
+
+impl Guardian {
+    /// Load the guardian file at `path`, or the default location.
+    /// `pin_was_set` is the marker kept in settings.
+    pub fn new(path: Option<PathBuf>, pin_was_set: bool) -> Self {
+        let path = path.unwrap_or_else(Self::default_path);
+        let state = match GuardianFile::load(&path) {
+            Ok(None) if pin_was_set => {
+                log::error!("Guardian file is missing; privileged actions stay locked until the PIN is reset");
+                GuardianState { damaged: true, ..Default::default() }
+            }
+            Ok(file) => GuardianState { file, ..Default::default() },
+            Err(e) => {
+                log::error!("{}; privileged actions stay locked until the PIN is reset", e);
+                GuardianState { damaged: true, ..Default::default() }
+            }
+        };
+
+        Self {
+            path,
+            state: Mutex::new(state),
+        }
+    }
+
+    fn default_path() -> PathBuf {
+        let config_dir = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
+
+        config_dir.join("FeelingWise").join("guardian.json")
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, GuardianState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Whether a PIN is set, or the guardian file is damaged
+    pub fn is_enabled(&self) -> bool {
+        let state = self.lock_state();
+        state.file.is_some() || state.damaged
+    }
+
+    pub fn status(&self) -> GuardianStatus {
+        let state = self.lock_state();
+        let locked_out_secs = state
+            .locked_until
+            .map(|until| until.saturating_duration_since(Instant::now()).as_secs())
+            .unwrap_or(0);
+
+        GuardianStatus {
+            enabled: state.file.is_some() || state.damaged,
+            damaged: state.damaged,
+            reset_at: state.file.as_ref().and_then(|f| f.reset_at),
+            locked_out_secs,
+            failed_attempts: state
+                .file
+                .as_ref()
+                .map(|f| f.failed_attempts.clone())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Allow a privileged action. Always allowed while no PIN is set, never
+    /// while the guardian file is damaged.
+    pub fn authorize(&self, token: Option<&str>) -> Result<(), String> {
+        let mut state = self.lock_state();
+        if state.damaged {
+            return Err(DAMAGED.to_string());
+        }
+        if state.file.is_none() {
+            return Ok(());
+        }
+
+        let now = Instant::now();
+        state.tokens.retain(|_, expires| *expires > now);
+        match token {
+            Some(token) if state.tokens.contains_key(token) => Ok(()),
+            _ => Err("Unlock with the guardian PIN first".to_string()),
+        }
+    }
+
+    /// Set or change the PIN. Changing an existing PIN needs an unlock token.
+    pub fn set_pin(&self, token: Option<&str>, pin: &str) -> Result<(), String> {
+        self.authorize(token)?;
+        if pin.chars().count() < MIN_PIN_LENGTH {
+            return Err(format!("PIN must be at least {} characters", MIN_PIN_LENGTH));
+        }
+
+        let file = GuardianFile::new(pin)?;
+        file.save(&self.path)?;
+
+        let mut state = self.lock_state();
+        state.file = Some(file);
+        state.tokens.clear();
+        state.consecutive_failures = 0;
+        state.locked_until = None;
+        log::info!("Guardian PIN set");
+        Ok(())
+    }
+
+    /// Remove the PIN, turning the guardian lock off
+    pub fn clear_pin(&self, token: Option<&str>) -> Result<(), String> {
+        self.authorize(token)?;
+        if self.path.exists() {
+            fs::remove_file(&self.path)
+                .map_err(|e| format!("Failed to remove guardian file: {}", e))?;
+        }
+
+        *self.lock_state() = GuardianState::default();
+        log::info!("Guardian PIN removed");
+        Ok(())
+    }
+
+    /// Replace a damaged guardian file with a new PIN. The damaged file is
+    /// kept next to it and the reset time is shown in the status.
+    pub fn reset_damaged(&self, pin: &str) -> Result<(), String> {
+        if !self.lock_state().damaged {
+            return Err("The guardian file isn't damaged; unlock to change the PIN".to_string());
+        }
+        if pin.chars().count() < MIN_PIN_LENGTH {
+            return Err(format!("PIN must be at least {} characters", MIN_PIN_LENGTH));
+        }
+
+        let damaged_copy = self.path.with_extension(format!("json.damaged-{}", unix_now()));
+        if self.path.exists() {
+            fs::rename(&self.path, &damaged_copy)
+                .map_err(|e| format!("Failed to move damaged guardian file aside: {}", e))?;
+        }
+        let mut file = GuardianFile::new(pin)?;
+        file.reset_at = Some(unix_now());
+        file.save(&self.path)?;
+
+        *self.lock_state() = GuardianState { file: Some(file), ..Default::default() };
+        log::warn!("Guardian PIN reset after the guardian file was found damaged ({:?} kept)", damaged_copy);
+        Ok(())
+    }
+
+    /// Check the PIN and hand out a token valid for [`UNLOCK_TTL`]
+    pub fn unlock(&self, pin: &str) -> Result<UnlockToken, String> {
+        let file = {
+            let state = self.lock_state();
+            if state.damaged {
+                return Err(DAMAGED.to_string());
+            }
+            if let Some(until) = state.locked_until {
+                let remaining = until.saturating_duration_since(Instant::now());
+                if !remaining.is_zero() {
+                    return Err(format!(
+                        "Too many failed attempts. Try again in {} seconds",
+                        remaining.as_secs().max(1)
+                    ));
+                }
+            }
+            state.file.clone().ok_or("No guardian PIN is set")?
+        };
+
+        // Hash outside the lock; Argon2 is deliberately slow
+        let matches = file.verify(pin)?;
+
+        let mut state = self.lock_state();
+        if !matches {
+            state.consecutive_failures += 1;
+            log::warn!(
+                "Guardian unlock failed ({} in a row)",
+                state.consecutive_failures
+            );
+            if state.consecutive_failures >= MAX_FAILED_ATTEMPTS {
+                state.consecutive_failures = 0;
+                state.locked_until = Some(Instant::now() + LOCKOUT);
+                log::warn!("Guardian unlock paused for {} seconds", LOCKOUT.as_secs());
+            }
+
+            if let Some(file) = state.file.as_mut() {
+                file.failed_attempts.push(unix_now());
+                let excess = file.failed_attempts.len().saturating_sub(FAILED_ATTEMPTS_KEPT);
+                file.failed_attempts.drain(..excess);
+                if let Err(e) = file.save(&self.path) {
+                    log::error!("Failed to record failed unlock attempt: {}", e);
+                }
+            }
+            return Err("Incorrect PIN".to_string());
+        }
+
+        state.consecutive_failures = 0;
+        state.locked_until = None;
+
+        let mut bytes = [0u8; 32];
+        OsRng.fill_bytes(&mut bytes);
+        let token = hex::encode(bytes);
+        let now = Instant::now();
+        state.tokens.retain(|_, expires| *expires > now);
+        state.tokens.insert(token.clone(), now + UNLOCK_TTL);
+
+        Ok(UnlockToken {
+            token,
+            expires_at: unix_now() + UNLOCK_TTL.as_secs() as i64,
+        })
+    }
+
+    /// Revoke every outstanding unlock token
+    pub fn lock(&self) {
+        self.lock_state().tokens.clear();
+    }
+}
+
+fn unix_now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pin_unlock_and_lockout() {
+        let dir = std::env::temp_dir().join(format!("feelingwise-guardian-{}", std::process::id()));
+        let path = dir.join("guardian.json");
+        let _ = fs::remove_dir_all(&dir);
+
+        let guardian = Guardian::new(Some(path.clone()), false);
+        assert!(!guardian.is_enabled());
+        assert!(guardian.authorize(None).is_ok());
+        assert!(guardian.set_pin(None, "12").is_err());
+        guardian.set_pin(None, "2468").unwrap();
+
+        // Changing the PIN or acting now needs a token
+        assert!(guardian.authorize(None).is_err());
+        assert!(guardian.set_pin(None, "1357").is_err());
+
+        let token = guardian.unlock("2468").unwrap().token;
+        assert!(guardian.authorize(Some(&token)).is_ok());
+        assert!(guardian.authorize(Some("forged")).is_err());
+        guardian.lock();
+        assert!(guardian.authorize(Some(&token)).is_err());
+
+        // Failures are logged to disk and pause unlocking
+        for _ in 0..MAX_FAILED_ATTEMPTS {
+            assert_eq!(guardian.unlock("0000").unwrap_err(), "Incorrect PIN");
+        }
+        assert!(guardian.unlock("2468").unwrap_err().starts_with("Too many"));
+        assert!(guardian.status().locked_out_secs > 0);
+
+        let reloaded = Guardian::new(Some(path.clone()), true);
+        assert!(reloaded.is_enabled());
+        assert_eq!(reloaded.status().failed_attempts.len(), MAX_FAILED_ATTEMPTS as usize);
+        assert!(!fs::read_to_string(&path).unwrap().contains("2468"));
+
+        let token = reloaded.unlock("2468").unwrap().token;
+        reloaded.clear_pin(Some(&token)).unwrap();
+        assert!(!reloaded.is_enabled());
+        assert!(!path.exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_damaged_file_fails_closed() {
+        let dir = std::env::temp_dir().join(format!("feelingwise-guardian-damaged-{}", std::process::id()));
+        let path = dir.join("guardian.json");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&path, "{ not json").unwrap();
+
+        let guardian = Guardian::new(Some(path.clone()), true);
+        assert!(guardian.is_enabled());
+        assert!(guardian.status().damaged);
+        assert!(guardian.authorize(None).is_err());
+        assert!(guardian.unlock("2468").is_err());
+        assert!(guardian.set_pin(None, "2468").is_err());
+        assert!(guardian.clear_pin(None).is_err());
+
+        // Resetting sets a new PIN and leaves a trace
+        guardian.reset_damaged("2468").unwrap();
+        let status = guardian.status();
+        assert!(!status.damaged && status.enabled && status.reset_at.is_some());
+        assert!(guardian.authorize(None).is_err());
+        let token = guardian.unlock("2468").unwrap().token;
+        assert!(guardian.authorize(Some(&token)).is_ok());
+        assert!(guardian.reset_damaged("1357").is_err());
+        assert!(Guardian::new(Some(path.clone()), true).status().reset_at.is_some());
+
+        // Deleting the file doesn't remove the lock while settings remember it
+        fs::remove_file(&path).unwrap();
+        let deleted = Guardian::new(Some(path), true);
+        assert!(deleted.status().damaged);
+        assert!(deleted.authorize(None).is_err());
+        deleted.reset_damaged("8642").unwrap();
+        assert!(deleted.status().reset_at.is_some());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,386 @@
+//! Ollama Installer Module
+//!
+//! Installs Ollama per-user from a locally supplied installer or archive, so
+//! parents (and offline or enterprise deployments) don't need a browser.
+//! Every package is copied into a private staging directory and checked
+//! against a SHA-256 checksum supplied by the caller; only the staged copy
+//! that was hashed is extracted or run.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+const HASH_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Installation step being reported
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallStage {
+    Verifying,
+    Extracting,
+    Installing,
+    Done,
+    Failed,
+}
+
+/// Progress event emitted while installing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstallProgress {
+    pub stage: InstallStage,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub percent: Option<u8>,
+    pub message: String,
+}
+
+impl InstallProgress {
+    fn new(stage: InstallStage, percent: Option<u8>, message: &str) -> Self {
+        Self {
+            stage,
+            percent,
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Kind of package, decided from the file name
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PackageKind {
+    /// `.tgz` / `.tar.gz` release archive (Linux)
+    TarGz,
+    /// `.zip` release archive (Windows, macOS)
+    Zip,
+    /// `OllamaSetup.exe` style installer (Windows)
+    SetupExe,
+    /// A bare `ollama` binary
+    Binary,
+}
+
+impl PackageKind {
+    fn detect(path: &Path) -> Self {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        if name.ends_with(".tgz") || name.ends_with(".tar.gz") {
+            Self::TarGz
+        } else if name.ends_with(".zip") {
+            Self::Zip
+        } else if name.ends_with(".exe") && name.contains("setup") {
+            Self::SetupExe
+        } else {
+            Self::Binary
+        }
+    }
+}
+
+/// Install Ollama from a local package.
+///
+/// `expected_sha256` must come from a source the package didn't travel with,
+/// such as the official release page; a checksum file next to the package
+/// would only catch a corrupted download, not a swapped one.
+pub fn install_from_file<F>(
+    package: &Path,
+    expected_sha256: &str,
+    progress: F,
+) -> Result<PathBuf, String>
+where
+    F: Fn(InstallProgress),
+{
+    let result = run_install(package, expected_sha256, &progress);
+    match &result {
+        Ok(path) => {
+            log::info!("Ollama installed at {:?}", path);
+            progress(InstallProgress::new(InstallStage::Done, Some(100), "Ollama installed"));
+        }
+        Err(e) => {
+            log::error!("Ollama installation failed: {}", e);
+            progress(InstallProgress::new(InstallStage::Failed, None, e));
+        }
+    }
+    result
+}
+
+fn run_install<F>(package: &Path, expected_sha256: &str, progress: &F) -> Result<PathBuf, String>
+where
+    F: Fn(InstallProgress),
+{
+    if !package.is_file() {
+        return Err(format!("Installer not found: {}", package.display()));
+    }
+    let expected = normalize_checksum(expected_sha256)?;
+    let file_name = package
+        .file_name()
+        .ok_or_else(|| format!("Invalid installer path: {}", package.display()))?;
+
+    let staging_dir = std::env::temp_dir().join(format!("feelingwise-install-{}", uuid::Uuid::new_v4()));
+    let mut builder = fs::DirBuilder::new();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+    builder
+        .create(&staging_dir)
+        .map_err(|e| format!("Failed to create staging directory: {}", e))?;
+    let staged = staging_dir.join(file_name);
+
+    let result = stage_package(package, &staged, |percent| {
+        progress(InstallProgress::new(
+            InstallStage::Verifying,
+            Some(percent),
+            "Verifying installer",
+        ));
+    })
+    .and_then(|actual| {
+        if actual != expected {
+            return Err(format!(
+                "Checksum mismatch: expected {}, got {}",
+                expected, actual
+            ));
+        }
+        install_staged(&staged, progress)
+    });
+
+    let _ = fs::remove_dir_all(&staging_dir);
+    result
+}
+
+/// Install from a staged package whose checksum has been checked
+fn install_staged<F>(package: &Path, progress: &F) -> Result<PathBuf, String>
+where
+    F: Fn(InstallProgress),
+{
+    let install_dir = install_dir().ok_or("Could not determine install directory")?;
+    fs::create_dir_all(&install_dir)
+        .map_err(|e| format!("Failed to create install directory: {}", e))?;
+
+    match PackageKind::detect(package) {
+        PackageKind::TarGz => {
+            progress(InstallProgress::new(InstallStage::Extracting, None, "Extracting Ollama"));
+            run(Command::new("tar").arg("-xzf").arg(package).arg("-C").arg(&install_dir))?;
+        }
+        PackageKind::Zip => {
+            progress(InstallProgress::new(InstallStage::Extracting, None, "Extracting Ollama"));
+            extract_zip(package, &install_dir)?;
+        }
+        PackageKind::SetupExe => {
+            progress(InstallProgress::new(InstallStage::Installing, None, "Running Ollama setup"));
+            // Inno Setup flags for a silent, per-user install
+            run(Command::new(package).args([
+                "/VERYSILENT",
+                "/SUPPRESSMSGBOXES",
+                "/NORESTART",
+                "/CURRENTUSER",
+            ]))?;
+        }
+        PackageKind::Binary => {
+            progress(InstallProgress::new(InstallStage::Installing, None, "Copying Ollama"));
+            let target = installed_binary_path().ok_or("Could not determine install path")?;
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+            }
+            fs::copy(package, &target).map_err(|e| format!("Failed to copy Ollama: {}", e))?;
+        }
+    }
+
+    let binary = installed_binary_path().ok_or("Could not determine install path")?;
+    if !binary.is_file() {
+        return Err(format!(
+            "Installer finished but {} was not found",
+            binary.display()
+        ));
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&binary, fs::Permissions::from_mode(0o755))
+            .map_err(|e| format!("Failed to mark Ollama executable: {}", e))?;
+    }
+
+    Ok(binary)
+}
+
+/// Per-user directory packages are installed into
+fn install_dir() -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    {
+        dirs::data_local_dir().map(|p| p.join("Programs").join("Ollama"))
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        dirs::home_dir().map(|p| p.join("Applications"))
+    }
+
+    // Release archives contain bin/ and lib/, so extracting into ~/.local
+    // puts the binary in ~/.local/bin
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        dirs::home_dir().map(|p| p.join(".local"))
+    }
+}
+
+/// Where the binary ends up after a per-user install; the supervisor
+/// searches this path
+pub fn installed_binary_path() -> Option<PathBuf> {
+    let dir = install_dir()?;
+
+    #[cfg(target_os = "windows")]
+    {
+        Some(dir.join("ollama.exe"))
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        Some(dir.join("Ollama.app").join("Contents").join("Resources").join("ollama"))
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        Some(dir.join("bin").join("ollama"))
+    }
+}
+
+/// Extract a zip archive with the platform's own tooling
+fn extract_zip(archive: &Path, dest: &Path) -> Result<(), String> {
+    // tar ships with Windows 10 and later and reads zip archives; paths go
+    // in as arguments, never through a shell
+    #[cfg(target_os = "windows")]
+    {
+        run(Command::new("tar").arg("-xf").arg(archive).arg("-C").arg(dest))
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        run(Command::new("ditto").arg("-x").arg("-k").arg(archive).arg(dest))
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        run(Command::new("unzip").arg("-o").arg(archive).arg("-d").arg(dest))
+    }
+}
+
+/// Run a command to completion, failing on a non-zero exit
+fn run(cmd: &mut Command) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    cmd.creation_flags(CREATE_NO_WINDOW);
+
+    let output = cmd
+        .output()
+        .map_err(|e| format!("Failed to run {:?}: {}", cmd.get_program(), e))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "{:?} failed: {}",
+            cmd.get_program(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// Copy `package` to `staged` through a single open handle, hashing the
+/// bytes as they are written, and report progress as a percentage. The
+/// returned hash is of exactly what `staged` holds, whatever happens to
+/// `package` afterwards.
+fn stage_package<F>(package: &Path, staged: &Path, on_progress: F) -> Result<String, String>
+where
+    F: Fn(u8),
+{
+    let mut file = File::open(package).map_err(|e| format!("Failed to open installer: {}", e))?;
+    let total = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let mut copy = File::create(staged).map_err(|e| format!("Failed to stage installer: {}", e))?;
+
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; HASH_CHUNK_SIZE];
+    let mut read_total = 0u64;
+    let mut last_percent = 0u8;
+
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .map_err(|e| format!("Failed to read installer: {}", e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        copy.write_all(&buffer[..read])
+            .map_err(|e| format!("Failed to stage installer: {}", e))?;
+        read_total += read as u64;
+
+        if let Some(percent) = (read_total * 100).checked_div(total) {
+            let percent = percent as u8;
+            if percent != last_percent {
+                last_percent = percent;
+                on_progress(percent);
+            }
+        }
+    }
+
+    copy.sync_all().map_err(|e| format!("Failed to stage installer: {}", e))?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Validate a hex SHA-256 checksum and lowercase it
+fn normalize_checksum(sum: &str) -> Result<String, String> {
+    let sum = sum.trim().trim_start_matches("sha256:").to_lowercase();
+    if sum.len() == 64 && sum.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(sum)
+    } else {
+        Err(format!("Invalid SHA-256 checksum: {}", sum))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_package_kind_detection() {
+        assert_eq!(PackageKind::detect(Path::new("ollama-linux-amd64.tgz")), PackageKind::TarGz);
+        assert_eq!(PackageKind::detect(Path::new("Ollama-darwin.zip")), PackageKind::Zip);
+        assert_eq!(PackageKind::detect(Path::new("OllamaSetup.exe")), PackageKind::SetupExe);
+        assert_eq!(PackageKind::detect(Path::new("ollama")), PackageKind::Binary);
+    }
+
+    #[test]
+    fn test_stages_what_it_hashes() {
+        let dir = std::env::temp_dir().join(format!("fw-installer-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let (package, staged) = (dir.join("ollama"), dir.join("staged"));
+        fs::write(&package, b"not really ollama").unwrap();
+
+        let actual = stage_package(&package, &staged, |_| {}).unwrap();
+        assert_eq!(actual, hex::encode(Sha256::digest(b"not really ollama")));
+
+        // Swapping the original afterwards doesn't change what gets installed
+        fs::write(&package, b"something else").unwrap();
+        assert_eq!(fs::read(&staged).unwrap(), b"not really ollama");
+
+        let wrong = "0".repeat(64);
+        assert!(install_from_file(&package, &wrong, |_| {}).unwrap_err().contains("mismatch"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_rejects_bad_checksum_format() {
+        assert!(normalize_checksum("abc123").is_err());
+        assert!(normalize_checksum(&"g".repeat(64)).is_err());
+        assert!(normalize_checksum(&format!("sha256:{}", "A".repeat(64))).is_ok());
+    }
+}
@@ -1,30 +1,61 @@
+mod actions;
+mod analytics;
 mod cache;
+mod crypto;
 mod extension_bridge;
+mod governor;
+mod guardian;
 mod hardware;
+mod installer;
+mod managed;
+mod neutralization;
 mod ollama;
+mod profiles;
+mod report;
+mod schedule;
 mod settings;
 mod supervisor;
 
-use cache::{CacheStats, CachedNeutralization, NeutralizationCache};
+use actions::ActionPolicy;
+use analytics::{Analytics, AnalyticsSummary, EventSource, NeutralizationEvent};
+use cache::{
+    CacheInvalidation, CacheKey, CachePolicy, CacheStats, CacheUsage, CachedNeutralization, ContentSource,
+    EncryptionStatus, HistoryPage, HistoryQuery, ImportReport, NeutralizationCache,
+};
+use extension_bridge::BridgeEvents;
+use governor::{GovernorMode, GovernorStatus, ResourceGovernor};
+use guardian::{Guardian, GuardianStatus, UnlockToken};
 use hardware::SystemInfo;
+use managed::{EffectiveSettings, ImportedSettings, ManagedPolicy};
 use ollama::{OllamaManager, OllamaStatus, RecommendedModel};
+use report::{DigestFiles, WeeklyDigest};
+use schedule::EffectivePolicy;
 use settings::{autostart, AppSettings};
-use supervisor::{FriendlyStatus, OllamaSupervisor, SupervisorConfig};
+use supervisor::{
+    CanaryResult, FriendlyStatus, ModelAvailability, ModelPullStatus, OllamaSupervisor,
+    SupervisorConfig, SupervisorEvent,
+};
 
 use std::sync::Arc;
 use tauri::{
-    menu::{Menu, MenuItem},
+    menu::{CheckMenuItem, IsMenuItem, Menu, MenuItem, Submenu},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    Manager, State, WindowEvent,
+    AppHandle, Emitter, Manager, State, WindowEvent,
 };
 use tokio::sync::Mutex;
 
 // Application state
 pub struct AppState {
     pub ollama: Arc<Mutex<OllamaManager>>,
-    pub cache: Arc<Mutex<NeutralizationCache>>,
-    pub supervisor: Arc<Mutex<OllamaSupervisor>>,
+    pub cache: NeutralizationCache,
+    pub analytics: Analytics,
+    pub supervisor: Arc<OllamaSupervisor>,
+    pub governor: Arc<ResourceGovernor>,
     pub settings: Arc<Mutex<AppSettings>>,
+    pub bridge: Arc<BridgeEvents>,
+    pub guardian: Arc<Guardian>,
+    /// Administrator policy from the system config directory, if installed
+    pub managed: Option<ManagedPolicy>,
 }
 
 // ============================================================================
@@ -46,8 +77,8 @@ async fn check_system_requirements() -> Result<SystemInfo, String> {
 // ============================================================================
 
 #[tauri::command]
-async fn check_ollama_installed() -> bool {
-    OllamaSupervisor::is_installed()
+async fn check_ollama_installed(state: State<'_, AppState>) -> Result<bool, String> {
+    Ok(state.supervisor.is_installed())
 }
 
 #[tauri::command]
@@ -58,27 +89,39 @@ async fn get_ollama_status(state: State<'_, AppState>) -> Result<OllamaStatus, S
 
 #[tauri::command]
 async fn get_friendly_status(state: State<'_, AppState>) -> Result<FriendlyStatus, String> {
-    let supervisor = state.supervisor.lock().await;
-    Ok(supervisor.get_friendly_status().await)
+    Ok(state.supervisor.get_friendly_status().await)
+}
+
+#[tauri::command]
+async fn get_inference_health(state: State<'_, AppState>) -> Result<Option<CanaryResult>, String> {
+    Ok(state.supervisor.last_canary().await)
+}
+
+#[tauri::command]
+async fn run_inference_check(state: State<'_, AppState>) -> Result<CanaryResult, String> {
+    Ok(state.supervisor.check_inference().await)
+}
+
+#[tauri::command]
+fn get_governor_status(state: State<'_, AppState>) -> GovernorStatus {
+    state.governor.status()
 }
 
 #[tauri::command]
 async fn start_ollama(state: State<'_, AppState>) -> Result<(), String> {
-    let supervisor = state.supervisor.lock().await;
-    supervisor.start().await
+    state.supervisor.start().await
 }
 
 #[tauri::command]
-async fn stop_ollama(state: State<'_, AppState>) -> Result<(), String> {
-    let supervisor = state.supervisor.lock().await;
-    supervisor.stop().await
+async fn stop_ollama(state: State<'_, AppState>, guardian_token: Option<String>) -> Result<(), String> {
+    state.guardian.authorize(guardian_token.as_deref())?;
+    state.supervisor.stop().await
 }
 
 #[tauri::command]
 async fn restart_ollama(state: State<'_, AppState>) -> Result<(), String> {
-    let supervisor = state.supervisor.lock().await;
-    supervisor.reset_restart_count();
-    supervisor.restart().await
+    state.supervisor.reset_restart_count();
+    state.supervisor.restart().await
 }
 
 #[tauri::command]
@@ -94,13 +137,54 @@ fn get_recommended_models() -> Vec<RecommendedModel> {
 }
 
 #[tauri::command]
-async fn pull_model(state: State<'_, AppState>, model_name: String) -> Result<(), String> {
+async fn pull_model(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    model_name: String,
+) -> Result<(), String> {
     let ollama = state.ollama.lock().await;
+    let model_for_progress = model_name.clone();
     ollama
-        .pull_model(&model_name, |progress| {
+        .pull_model(&model_name, move |progress| {
             log::info!("Pull progress: {:?}", progress);
+            let status = ModelPullStatus {
+                model: model_for_progress.clone(),
+                status: progress.status,
+                completed: progress.completed,
+                total: progress.total,
+                done: false,
+                error: None,
+            };
+            let _ = app.emit("supervisor-event", SupervisorEvent::ModelPull(status));
         })
-        .await
+        .await?;
+
+    // Re-pin the digest if the selected model was (re)downloaded
+    if state.supervisor.model_requirement().name == model_name {
+        state.supervisor.reset_pull_backoff();
+        state.supervisor.clear_model_pin();
+    }
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_model_availability(state: State<'_, AppState>) -> Result<ModelAvailability, String> {
+    state.supervisor.verify_model().await
+}
+
+/// Trust the model as installed now after its digest changed outside the app
+#[tauri::command]
+async fn repin_model(
+    state: State<'_, AppState>,
+    guardian_token: Option<String>,
+) -> Result<ModelAvailability, String> {
+    state.guardian.authorize(guardian_token.as_deref())?;
+    state.supervisor.repin_model().await
+}
+
+#[tauri::command]
+fn get_model_pull_status(state: State<'_, AppState>) -> Option<ModelPullStatus> {
+    state.supervisor.pull_status()
 }
 
 // ============================================================================
@@ -114,7 +198,20 @@ async fn get_settings(state: State<'_, AppState>) -> Result<AppSettings, String>
 }
 
 #[tauri::command]
-async fn save_settings(state: State<'_, AppState>, new_settings: AppSettings) -> Result<(), String> {
+async fn save_settings(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    new_settings: AppSettings,
+    guardian_token: Option<String>,
+) -> Result<(), String> {
+    state.guardian.authorize(guardian_token.as_deref())?;
+    replace_settings(&app, &state, new_settings).await
+}
+
+/// Validate and apply a full set of settings from the UI or an import
+async fn replace_settings(app: &AppHandle, state: &AppState, mut new_settings: AppSettings) -> Result<(), String> {
+    profiles::validate(&new_settings.profiles)?;
+    enforce_policy(state, &mut new_settings);
     let mut settings = state.settings.lock().await;
 
     // Handle auto-start changes
@@ -122,22 +219,185 @@ async fn save_settings(state: State<'_, AppState>, new_settings: AppSettings) ->
         autostart::set_enabled(new_settings.start_on_login)?;
     }
 
+    // Only the guardian commands change the PIN marker
+    new_settings.guardian_enabled = settings.guardian_enabled;
+
+    // A different model gets pinned again on its first verification
+    if settings.selected_model != new_settings.selected_model {
+        new_settings.selected_model_digest = None;
+    }
+
     *settings = new_settings;
-    settings.save()
+    apply_settings(state, &settings);
+    settings.save()?;
+    state.bridge.settings_changed();
+    refresh_tray_menu(app, &settings);
+
+    apply_cache_policy(&state.cache, settings.cache_policy.clone()).await
+}
+
+/// Apply a cache policy off the async runtime. Turning on privacy mode
+/// scrubs stored originals, and the digests quoting them are wiped too.
+async fn apply_cache_policy(cache: &NeutralizationCache, policy: CachePolicy) -> Result<(), String> {
+    cache
+        .run(move |c| {
+            let scrubs = policy.privacy_mode && !c.policy().privacy_mode;
+            c.set_policy(policy)?;
+            if scrubs {
+                report::wipe_digests(&report::reports_dir())?;
+            }
+            Ok(())
+        })
+        .await?
 }
 
 #[tauri::command]
-async fn set_language(state: State<'_, AppState>, lang: String) -> Result<(), String> {
+async fn set_language(
+    state: State<'_, AppState>,
+    lang: String,
+    guardian_token: Option<String>,
+) -> Result<(), String> {
+    state.guardian.authorize(guardian_token.as_deref())?;
+    ensure_unlocked(&state, "language")?;
     let mut settings = state.settings.lock().await;
     settings.language = lang;
-    settings.save()
+    settings.save()?;
+    state.bridge.settings_changed();
+    Ok(())
 }
 
 #[tauri::command]
-async fn complete_first_run(state: State<'_, AppState>) -> Result<(), String> {
+async fn complete_first_run(state: State<'_, AppState>, guardian_token: Option<String>) -> Result<(), String> {
+    state.guardian.authorize(guardian_token.as_deref())?;
     let mut settings = state.settings.lock().await;
     settings.first_run_complete = true;
-    settings.save()
+    settings.save()?;
+    state.bridge.settings_changed();
+    Ok(())
+}
+
+/// Hand settings to the components that keep their own copy
+fn apply_settings(state: &AppState, settings: &AppSettings) {
+    state.supervisor.set_model_requirement(settings.model_requirement());
+    state.supervisor.set_launch_options(settings.launch_options());
+    state.governor.set_policy(settings.resource_policy.clone());
+    refresh_policy(state, settings);
+}
+
+/// Evaluate the active profile's schedule and announce a changed policy
+fn refresh_policy(state: &AppState, settings: &AppSettings) {
+    let policy = schedule::evaluate(&settings.active_profile(), chrono::Local::now());
+    if state.supervisor.set_effective_policy(policy.clone()) {
+        log::info!(
+            "Protection now follows profile {} ({})",
+            policy.profile_name,
+            policy.window.as_deref().unwrap_or("no schedule window")
+        );
+        state.bridge.policy_changed(policy);
+    }
+}
+
+/// How often profile schedules are re-evaluated
+const POLICY_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Let schedule windows start and end without a settings change
+async fn run_policy_schedule(app: AppHandle) {
+    loop {
+        tokio::time::sleep(POLICY_CHECK_INTERVAL).await;
+        if let Some(state) = app.try_state::<AppState>() {
+            let settings = state.settings.lock().await.clone();
+            refresh_policy(&state, &settings);
+        }
+    }
+}
+
+/// Built-in action policies by persona, for the UI to start from
+#[tauri::command]
+fn get_action_presets() -> std::collections::BTreeMap<String, ActionPolicy> {
+    profiles::PERSONAS
+        .iter()
+        .map(|persona| (persona.to_string(), ActionPolicy::preset(persona)))
+        .collect()
+}
+
+/// The active profile with its schedule applied
+#[tauri::command]
+fn get_effective_policy(state: State<'_, AppState>) -> EffectivePolicy {
+    state.supervisor.effective_policy()
+}
+
+/// Adopt settings edited outside the app and tell the UI and extension
+async fn reload_settings(app: AppHandle, mut reloaded: AppSettings) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+
+    // Hand-edited files could turn protection off without the PIN
+    if state.guardian.is_enabled() {
+        log::warn!("Ignoring settings edited outside the app while the guardian lock is on");
+        if let Err(e) = state.settings.lock().await.save() {
+            log::error!("Failed to restore settings: {}", e);
+        }
+        return;
+    }
+
+    {
+        let mut settings = state.settings.lock().await;
+        if state.managed.as_ref().is_some_and(|policy| !policy.apply(&mut reloaded).is_empty()) {
+            log::warn!("Restoring settings set by the managed policy");
+            if let Err(e) = reloaded.save() {
+                log::error!("Failed to restore settings: {}", e);
+            }
+        }
+        if settings.start_on_login != reloaded.start_on_login {
+            if let Err(e) = autostart::set_enabled(reloaded.start_on_login) {
+                log::error!("Failed to update auto-start: {}", e);
+            }
+        }
+        *settings = reloaded.clone();
+        apply_settings(&state, &settings);
+    }
+
+    if let Err(e) = apply_cache_policy(&state.cache, reloaded.cache_policy.clone()).await {
+        log::error!("Failed to apply cache policy: {}", e);
+    }
+
+    state.bridge.settings_changed();
+    refresh_tray_menu(&app, &reloaded);
+    let _ = app.emit("settings-changed", reloaded);
+}
+
+/// Switch profiles from the UI; needs an unlock token while a PIN is set
+#[tauri::command]
+async fn set_active_profile(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+    guardian_token: Option<String>,
+) -> Result<(), String> {
+    state.guardian.authorize(guardian_token.as_deref())?;
+    activate_profile(&app, &state, &id).await
+}
+
+/// Make `id` the active profile and tell the UI, the extension and the tray
+async fn activate_profile(app: &AppHandle, state: &AppState, id: &str) -> Result<(), String> {
+    ensure_unlocked(state, "active_profile")?;
+    let settings = {
+        let mut settings = state.settings.lock().await;
+        if !settings.profiles.iter().any(|p| p.id == id) {
+            return Err(format!("No profile with id \"{}\"", id));
+        }
+        settings.active_profile = id.to_string();
+        settings.save()?;
+        settings.clone()
+    };
+
+    log::info!("Switched to profile {}", id);
+    refresh_policy(state, &settings);
+    state.bridge.settings_changed();
+    refresh_tray_menu(app, &settings);
+    let _ = app.emit("settings-changed", settings);
+    Ok(())
 }
 
 #[tauri::command]
@@ -146,10 +406,155 @@ fn get_autostart_enabled() -> bool {
 }
 
 #[tauri::command]
-fn set_autostart_enabled(enabled: bool) -> Result<(), String> {
+fn set_autostart_enabled(
+    state: State<'_, AppState>,
+    enabled: bool,
+    guardian_token: Option<String>,
+) -> Result<(), String> {
+    state.guardian.authorize(guardian_token.as_deref())?;
+    ensure_unlocked(&state, "start_on_login")?;
     autostart::set_enabled(enabled)
 }
 
+// ============================================================================
+// MANAGED SETTINGS COMMANDS
+// ============================================================================
+
+/// Put values set by the managed policy back over `settings`
+fn enforce_policy(state: &AppState, settings: &mut AppSettings) {
+    if let Some(policy) = &state.managed {
+        let changed = policy.apply(settings);
+        if !changed.is_empty() {
+            log::warn!("Managed policy overrides {}", changed.join(", "));
+        }
+    }
+}
+
+/// Refuse to change a field the managed policy sets
+fn ensure_unlocked(state: &AppState, field: &str) -> Result<(), String> {
+    match &state.managed {
+        Some(policy) if policy.locks(field) => Err(format!("{} is set by your administrator", field)),
+        _ => Ok(()),
+    }
+}
+
+/// Settings in force, with which ones come from the managed policy
+#[tauri::command]
+async fn get_effective_settings(state: State<'_, AppState>) -> Result<EffectiveSettings, String> {
+    let settings = state.settings.lock().await;
+    Ok(EffectiveSettings::new(&settings, state.managed.as_ref()))
+}
+
+/// Write settings to a signed file; returns the signer's public key
+#[tauri::command]
+async fn export_settings(state: State<'_, AppState>, path: String) -> Result<String, String> {
+    let settings = state.settings.lock().await.clone();
+    tokio::task::spawn_blocking(move || managed::export_settings(&settings, std::path::Path::new(&path), None))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Verify a signed settings file and make it the current settings
+#[tauri::command]
+async fn import_settings(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+    guardian_token: Option<String>,
+) -> Result<ImportedSettings, String> {
+    state.guardian.authorize(guardian_token.as_deref())?;
+    let current = state.settings.lock().await.clone();
+    let imported = managed::import_settings(std::path::Path::new(&path), &current, state.managed.as_ref(), None)?;
+
+    replace_settings(&app, &state, imported.settings.clone()).await?;
+    log::info!("Imported settings signed by {}", imported.signer);
+    let settings = state.settings.lock().await.clone();
+    let _ = app.emit("settings-changed", &settings);
+    Ok(ImportedSettings { settings, ..imported })
+}
+
+// ============================================================================
+// GUARDIAN COMMANDS
+// ============================================================================
+
+#[tauri::command]
+fn get_guardian_status(state: State<'_, AppState>) -> GuardianStatus {
+    state.guardian.status()
+}
+
+/// Set or change the guardian PIN; changing it needs a current unlock token
+#[tauri::command]
+async fn set_guardian_pin(
+    state: State<'_, AppState>,
+    pin: String,
+    guardian_token: Option<String>,
+) -> Result<(), String> {
+    let guardian = state.guardian.clone();
+    tokio::task::spawn_blocking(move || guardian.set_pin(guardian_token.as_deref(), &pin))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))??;
+    set_guardian_marker(&state, true).await
+}
+
+#[tauri::command]
+async fn clear_guardian_pin(state: State<'_, AppState>, guardian_token: Option<String>) -> Result<(), String> {
+    state.guardian.clear_pin(guardian_token.as_deref())?;
+    set_guardian_marker(&state, false).await
+}
+
+/// Remember in settings whether a PIN is set, so a deleted guardian file
+/// is noticed on the next start
+async fn set_guardian_marker(state: &AppState, enabled: bool) -> Result<(), String> {
+    let mut settings = state.settings.lock().await;
+    if settings.guardian_enabled != enabled {
+        settings.guardian_enabled = enabled;
+        settings.save()?;
+    }
+    Ok(())
+}
+
+/// Set a new PIN over a guardian file that couldn't be read
+#[tauri::command]
+async fn reset_guardian_pin(state: State<'_, AppState>, pin: String) -> Result<(), String> {
+    let guardian = state.guardian.clone();
+    tokio::task::spawn_blocking(move || guardian.reset_damaged(&pin))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))??;
+    set_guardian_marker(&state, true).await
+}
+
+/// Check the PIN and return a token for privileged commands
+#[tauri::command]
+async fn unlock_guardian(state: State<'_, AppState>, pin: String) -> Result<UnlockToken, String> {
+    let guardian = state.guardian.clone();
+    tokio::task::spawn_blocking(move || guardian.unlock(&pin))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
+
+#[tauri::command]
+fn lock_guardian(state: State<'_, AppState>) {
+    state.guardian.lock();
+}
+
+#[tauri::command]
+async fn quit_app(app: AppHandle, state: State<'_, AppState>, guardian_token: Option<String>) -> Result<(), String> {
+    state.guardian.authorize(guardian_token.as_deref())?;
+    shutdown(app);
+    Ok(())
+}
+
+/// Stop Ollama, flush cache stats and exit
+fn shutdown(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        if let Some(state) = app.try_state::<AppState>() {
+            let _ = state.supervisor.stop().await;
+            let _ = state.cache.run(|c| c.flush_stats()).await;
+        }
+        std::process::exit(0);
+    });
+}
+
 // ============================================================================
 // HELPER COMMANDS
 // ============================================================================
@@ -159,6 +564,30 @@ async fn open_ollama_download() -> Result<(), String> {
     open::that("https://ollama.ai/download").map_err(|e| e.to_string())
 }
 
+/// Install Ollama from a local installer or archive, then start it
+#[tauri::command]
+async fn install_ollama(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    installer_path: String,
+    sha256: String,
+    guardian_token: Option<String>,
+) -> Result<(), String> {
+    state.guardian.authorize(guardian_token.as_deref())?;
+    let package = std::path::PathBuf::from(installer_path);
+    let progress_app = app.clone();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        installer::install_from_file(&package, &sha256, |progress| {
+            let _ = progress_app.emit("ollama-install-progress", progress);
+        })
+    })
+    .await
+    .map_err(|e| format!("Installer task failed: {}", e))??;
+
+    state.supervisor.start().await
+}
+
 #[tauri::command]
 async fn open_extension_store() -> Result<(), String> {
     // Chrome Web Store - update with actual extension ID when published
@@ -168,24 +597,18 @@ async fn open_extension_store() -> Result<(), String> {
 #[tauri::command]
 async fn get_setup_status(state: State<'_, AppState>) -> Result<SetupStatus, String> {
     let settings = state.settings.lock().await;
-    let supervisor = state.supervisor.lock().await;
-    let is_healthy = supervisor.is_healthy().await;
-
-    let models = if is_healthy {
-        let ollama = state.ollama.lock().await;
-        ollama
-            .list_models()
-            .await
-            .map(|m| m.iter().map(|info| info.name.clone()).collect())
-            .unwrap_or_default()
-    } else {
-        vec![]
-    };
+    let is_healthy = state.supervisor.is_healthy().await;
+
+    let model_available = is_healthy
+        && matches!(
+            state.supervisor.verify_model().await,
+            Ok(ModelAvailability::Available { .. })
+        );
 
     Ok(SetupStatus {
-        ollama_installed: OllamaSupervisor::is_installed(),
+        ollama_installed: state.supervisor.is_installed(),
         ollama_running: is_healthy,
-        model_available: !models.is_empty(),
+        model_available,
         first_run_complete: settings.first_run_complete,
     })
 }
@@ -202,90 +625,117 @@ pub struct SetupStatus {
 // NEUTRALIZATION COMMANDS
 // ============================================================================
 
-/// System prompt for content neutralization (from MASTER_BLUEPRINT)
-const NEUTRALIZATION_PROMPT: &str = r#"You are a content neutralization system. Your task is to transform emotionally manipulative social media text into neutral, factual language while preserving ALL original meaning and claims.
-
-## RULES (MUST FOLLOW):
-
-1. PRESERVE the author's viewpoint, concern, topic, and all factual claims
-2. REMOVE only manipulation techniques:
-   - ALL CAPS → normal case
-   - Excessive punctuation (!!!) → single punctuation
-   - Urgency language → factual timeline if applicable
-   - Fear appeals → neutral concern statement
-   - Ad hominem attacks → position-focused language
-   - Absolute language (everyone, always, never) → proportional (some, often, rarely)
-   - Alarm emojis (🚨🔥⚠️) → removed
-
-3. DO NOT:
-   - Add information not in the original
-   - Judge whether claims are true or false
-   - Use loaded verbs (claimed, alleged, admitted, revealed)
-   - Add warnings, disclaimers, or editorial comments
-   - Change the meaning or direction of the opinion
-
-4. OUTPUT FORMAT:
-   Return JSON with the following structure:
-   {
-     "neutralized": "The neutralized version of the text",
-     "techniques": ["List", "of", "detected", "techniques"],
-     "severity": 0-10
-   }
-
-Now neutralize this text:
-"#;
-
 #[tauri::command]
 async fn neutralize_content(
     state: State<'_, AppState>,
     content: String,
     model: Option<String>,
+    source: Option<ContentSource>,
 ) -> Result<CachedNeutralization, String> {
+    let started = std::time::Instant::now();
+    let source = source.unwrap_or_default().normalized();
+    let (key, profile, policy) = {
+        let settings = state.settings.lock().await;
+        let profile = settings.active_profile();
+        let policy = schedule::evaluate(&profile, chrono::Local::now());
+        let key = CacheKey {
+            model: model.unwrap_or_else(|| settings.selected_model.clone()),
+            prompt_version: neutralization::prompt_version(),
+            persona: policy.persona.clone(),
+        };
+        (key, profile, policy)
+    };
+
+    if policy.paused {
+        return Err(format!(
+            "Protection is paused by the {} schedule",
+            policy.window.as_deref().unwrap_or_default()
+        ));
+    }
+    if !profile.covers_platform(&source.platform) {
+        return Err(format!(
+            "Protection is off for {} in the {} profile",
+            source.platform, profile.name
+        ));
+    }
+
     // Check cache first
-    {
-        let cache = state.cache.lock().await;
-        if let Some(cached) = cache.get(&content) {
-            log::info!("Cache hit for content");
-            return Ok(cached);
+    let lookup = (content.clone(), key.clone());
+    if let Some(mut cached) = state.cache.run(move |c| c.get(&lookup.0, &lookup.1)).await? {
+        log::info!("Cache hit for content");
+        // The entry may have been cached from another page
+        cached.source = source;
+        cached.action = Some(policy.actions.decide(&cached.techniques, cached.severity, policy.severity_threshold));
+        let origin = if cached.similarity.unwrap_or(1.0) < 1.0 {
+            EventSource::FuzzyCache
+        } else {
+            EventSource::Cache
+        };
+        record_event(&state, &cached, origin, &profile.id, started);
+        return Ok(cached);
+    }
+
+    // Back off when the machine is busy or on battery
+    match state.governor.mode() {
+        GovernorMode::Paused => {
+            return Err("Protection is paused to save system resources".to_string());
+        }
+        GovernorMode::RuleBased => {
+            let output = neutralization::rule_based(&content);
+            let action = policy.actions.decide(&output.techniques, output.severity, policy.severity_threshold);
+            let result = CachedNeutralization {
+                content_hash: NeutralizationCache::hash_content(&content),
+                original: content,
+                neutralized: output.neutralized,
+                techniques: output.techniques,
+                severity: output.severity,
+                created_at: std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_secs() as i64)
+                    .unwrap_or(0),
+                hit_count: 0,
+                model: "rule-based".to_string(),
+                prompt_version: key.prompt_version,
+                persona: key.persona,
+                source,
+                similarity: None,
+                action: Some(action),
+            };
+            record_event(&state, &result, EventSource::RuleBased, &profile.id, started);
+            return Ok(result);
+        }
+        GovernorMode::Throttled => {
+            let delay = state.governor.policy().throttle_delay_ms;
+            tokio::time::sleep(tokio::time::Duration::from_millis(delay)).await;
         }
+        GovernorMode::Normal => {}
     }
 
     // Not in cache, perform neutralization
     let ollama = state.ollama.lock().await;
-    let model_name = model.unwrap_or_else(|| "phi3:mini".to_string());
-
-    let prompt = format!("{}{}", NEUTRALIZATION_PROMPT, content);
-    let response = ollama.generate(&model_name, &prompt).await?;
-
-    // Parse the JSON response
-    let parsed: serde_json::Value = serde_json::from_str(&response)
-        .map_err(|e| format!("Failed to parse AI response: {}. Response: {}", e, response))?;
-
-    let neutralized = parsed["neutralized"]
-        .as_str()
-        .ok_or("Missing 'neutralized' field in response")?
-        .to_string();
-
-    let techniques: Vec<String> = parsed["techniques"]
-        .as_array()
-        .map(|arr| {
-            arr.iter()
-                .filter_map(|v| v.as_str().map(String::from))
-                .collect()
-        })
-        .unwrap_or_default();
 
-    let severity = parsed["severity"].as_i64().unwrap_or(0) as i32;
+    let prompt = neutralization::build_prompt(&content);
+    let response = ollama.generate(&key.model, &prompt).await?;
+
+    let neutralization::NeutralizationOutput {
+        neutralized,
+        techniques,
+        severity,
+    } = neutralization::parse_response(&response)?;
 
     // Store in cache
     {
-        let cache = state.cache.lock().await;
-        cache.set(&content, &neutralized, &techniques, severity)?;
+        let entry = (content.clone(), key.clone(), neutralized.clone(), techniques.clone(), source.clone());
+        state
+            .cache
+            .run(move |c| c.set(&entry.0, &entry.1, &entry.2, &entry.3, severity, &entry.4))
+            .await??;
     }
 
     let content_hash = NeutralizationCache::hash_content(&content);
+    let action = policy.actions.decide(&techniques, severity, policy.severity_threshold);
 
-    Ok(CachedNeutralization {
+    let result = CachedNeutralization {
         content_hash,
         original: content,
         neutralized,
@@ -296,7 +746,132 @@ async fn neutralize_content(
             .map(|d| d.as_secs() as i64)
             .unwrap_or(0),
         hit_count: 0,
+        model: key.model,
+        prompt_version: key.prompt_version,
+        persona: key.persona,
+        source,
+        similarity: None,
+        action: Some(action),
+    };
+    record_event(&state, &result, EventSource::Model, &profile.id, started);
+    Ok(result)
+}
+
+/// Record a neutralization for analytics without delaying the result
+fn record_event(
+    state: &AppState,
+    result: &CachedNeutralization,
+    source: EventSource,
+    profile: &str,
+    started: std::time::Instant,
+) {
+    let event = NeutralizationEvent {
+        timestamp: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0),
+        platform: result.source.platform.clone(),
+        techniques: result.techniques.clone(),
+        severity: result.severity,
+        latency_ms: started.elapsed().as_millis() as u64,
+        model: result.model.clone(),
+        source,
+        profile: profile.to_string(),
+    };
+    let analytics = state.analytics.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = analytics.run(move |a| a.record(&event)).await.and_then(|r| r) {
+            log::warn!("Failed to record analytics event: {}", e);
+        }
+    });
+}
+
+// ============================================================================
+// ANALYTICS COMMANDS
+// ============================================================================
+
+/// Technique, severity, time-of-day and latency aggregates for the last `days` days
+#[tauri::command]
+async fn get_analytics(state: State<'_, AppState>, days: Option<u32>) -> Result<AnalyticsSummary, String> {
+    state.analytics.run(move |a| a.summary(days)).await?
+}
+
+#[tauri::command]
+async fn clear_analytics(state: State<'_, AppState>, guardian_token: Option<String>) -> Result<(), String> {
+    state.guardian.authorize(guardian_token.as_deref())?;
+    state.analytics.run(|a| a.clear()).await?
+}
+
+// ============================================================================
+// REPORT COMMANDS
+// ============================================================================
+
+/// How often the digest schedule is checked
+const DIGEST_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15 * 60);
+
+/// Write a digest of the last seven days now
+#[tauri::command]
+async fn generate_digest(state: State<'_, AppState>) -> Result<DigestFiles, String> {
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    write_digest(state.analytics.clone(), state.cache.clone(), today).await
+}
+
+/// Open the newest digest in the default browser
+#[tauri::command]
+async fn open_latest_digest(app: AppHandle) -> Result<(), String> {
+    open_latest_report(&app)
+}
+
+/// Build a digest and write it under `reports_dir`, on the blocking pool
+async fn write_digest(
+    analytics: Analytics,
+    cache: NeutralizationCache,
+    day: String,
+) -> Result<DigestFiles, String> {
+    tokio::task::spawn_blocking(move || {
+        WeeklyDigest::build(&analytics, &cache)?.write(&report::reports_dir(), &day)
     })
+    .await
+    .map_err(|e| format!("Report worker failed: {}", e))?
+}
+
+fn open_latest_report(app: &AppHandle) -> Result<(), String> {
+    let path = report::latest_digest(&report::reports_dir())
+        .ok_or("No weekly report has been written yet")?;
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        let _ = tray.set_tooltip(Some("FeelingWise"));
+    }
+    open::that(&path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))
+}
+
+/// Write the weekly digest once its scheduled time has passed. Missed
+/// slots (app not running) are caught up on the next check.
+async fn run_digest_schedule(app: AppHandle) {
+    let dir = report::reports_dir();
+    loop {
+        if let Some(state) = app.try_state::<AppState>() {
+            let schedule = state.settings.lock().await.digest.clone();
+            if let Some(slot) = schedule.last_slot(chrono::Local::now()) {
+                let day = slot.format("%Y-%m-%d").to_string();
+                if !report::digest_exists(&dir, &day) {
+                    match write_digest(state.analytics.clone(), state.cache.clone(), day).await {
+                        Ok(files) if schedule.notify => notify_digest_ready(&app, &files),
+                        Ok(_) => {}
+                        Err(e) => log::error!("Failed to write weekly digest: {}", e),
+                    }
+                }
+            }
+        }
+        tokio::time::sleep(DIGEST_CHECK_INTERVAL).await;
+    }
+}
+
+/// Point the tray at a new digest and tell the UI
+fn notify_digest_ready(app: &AppHandle, files: &DigestFiles) {
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        let _ = tray.set_tooltip(Some("FeelingWise: a new weekly report is ready"));
+    }
+    let _ = app.emit("digest-ready", files);
 }
 
 // ============================================================================
@@ -305,14 +880,129 @@ async fn neutralize_content(
 
 #[tauri::command]
 async fn get_cache_stats(state: State<'_, AppState>) -> Result<CacheStats, String> {
-    let cache = state.cache.lock().await;
-    Ok(cache.get_stats())
+    state.cache.run(|c| c.get_stats()).await
+}
+
+#[tauri::command]
+async fn clear_cache(state: State<'_, AppState>, guardian_token: Option<String>) -> Result<(), String> {
+    state.guardian.authorize(guardian_token.as_deref())?;
+    state.cache.run(|c| c.clear()).await?
+}
+
+/// Entry count and disk size of the cache
+#[tauri::command]
+async fn get_cache_usage(state: State<'_, AppState>) -> Result<CacheUsage, String> {
+    state.cache.run(|c| c.usage()).await?
+}
+
+/// Search past results, newest first
+#[tauri::command]
+async fn search_history(state: State<'_, AppState>, query: HistoryQuery) -> Result<HistoryPage, String> {
+    state.cache.run(move |c| c.history(&query)).await?
+}
+
+/// Drop cached results for one piece of content, model, prompt version or persona
+#[tauri::command]
+async fn invalidate_cache(
+    state: State<'_, AppState>,
+    target: CacheInvalidation,
+    guardian_token: Option<String>,
+) -> Result<usize, String> {
+    state.guardian.authorize(guardian_token.as_deref())?;
+    state.cache.run(move |c| c.invalidate(&target)).await?
+}
+
+/// Write cached results to a file that another computer can import
+#[tauri::command]
+async fn export_cache(state: State<'_, AppState>, path: String) -> Result<usize, String> {
+    state.cache.run(move |c| c.export_to(std::path::Path::new(&path))).await?
+}
+
+/// Merge a cache export from another computer
+#[tauri::command]
+async fn import_cache(
+    state: State<'_, AppState>,
+    path: String,
+    guardian_token: Option<String>,
+) -> Result<ImportReport, String> {
+    state.guardian.authorize(guardian_token.as_deref())?;
+    let prompt_version = neutralization::prompt_version();
+    state
+        .cache
+        .run(move |c| c.import_from(std::path::Path::new(&path), &prompt_version))
+        .await?
 }
 
 #[tauri::command]
-async fn clear_cache(state: State<'_, AppState>) -> Result<(), String> {
-    let cache = state.cache.lock().await;
-    cache.clear()
+async fn get_cache_encryption_status(state: State<'_, AppState>) -> Result<EncryptionStatus, String> {
+    state.cache.run(|c| c.encryption_status()).await
+}
+
+/// Encrypt cached posts under a parental passphrase
+#[tauri::command]
+async fn enable_cache_encryption(
+    state: State<'_, AppState>,
+    passphrase: String,
+    guardian_token: Option<String>,
+) -> Result<(), String> {
+    state.guardian.authorize(guardian_token.as_deref())?;
+    // Digests quote originals the cache is about to protect
+    state
+        .cache
+        .run(move |c| {
+            c.enable_encryption(&passphrase)?;
+            report::wipe_digests(&report::reports_dir()).map(|_| ())
+        })
+        .await?
+}
+
+#[tauri::command]
+async fn unlock_cache(state: State<'_, AppState>, passphrase: String) -> Result<(), String> {
+    state.cache.run(move |c| c.unlock(&passphrase)).await?
+}
+
+#[tauri::command]
+async fn lock_cache(state: State<'_, AppState>) -> Result<(), String> {
+    state.cache.run(|c| c.lock()).await
+}
+
+/// Re-encrypt under a new key, optionally changing the passphrase
+#[tauri::command]
+async fn rotate_cache_key(
+    state: State<'_, AppState>,
+    passphrase: String,
+    new_passphrase: Option<String>,
+    guardian_token: Option<String>,
+) -> Result<(), String> {
+    state.guardian.authorize(guardian_token.as_deref())?;
+    state
+        .cache
+        .run(move |c| c.rotate_key(&passphrase, new_passphrase.as_deref()))
+        .await?
+}
+
+#[tauri::command]
+async fn disable_cache_encryption(
+    state: State<'_, AppState>,
+    passphrase: String,
+    guardian_token: Option<String>,
+) -> Result<(), String> {
+    state.guardian.authorize(guardian_token.as_deref())?;
+    state.cache.run(move |c| c.disable_encryption(&passphrase)).await?
+}
+
+/// Erase all cached posts, backups, the encryption key and the digests
+/// quoting them
+#[tauri::command]
+async fn wipe_cache(state: State<'_, AppState>, guardian_token: Option<String>) -> Result<(), String> {
+    state.guardian.authorize(guardian_token.as_deref())?;
+    state
+        .cache
+        .run(|c| {
+            c.secure_wipe()?;
+            report::wipe_digests(&report::reports_dir()).map(|_| ())
+        })
+        .await?
 }
 
 // ============================================================================
@@ -322,28 +1012,75 @@ async fn clear_cache(state: State<'_, AppState>) -> Result<(), String> {
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     // Load settings
-    let settings = AppSettings::load();
+    let mut settings = AppSettings::load();
+
+    // Administrator policy wins over user settings
+    let managed = ManagedPolicy::load();
+    if let Some(policy) = &managed {
+        if !policy.apply(&mut settings).is_empty() {
+            if let Err(e) = settings.save() {
+                log::error!("Failed to save managed settings: {}", e);
+            }
+        }
+    }
 
     // Initialize cache
-    let cache = NeutralizationCache::new(None).expect("Failed to initialize cache");
+    let cache = NeutralizationCache::new(None).unwrap_or_else(|e| {
+        log::error!("{}; falling back to a temporary in-memory cache", e);
+        NeutralizationCache::in_memory().expect("Failed to initialize in-memory cache")
+    });
+    if let Err(e) = cache.set_policy(settings.cache_policy.clone()) {
+        log::error!("Failed to apply cache policy: {}", e);
+    }
+
+    // Initialize analytics
+    let analytics = Analytics::new(None).unwrap_or_else(|e| {
+        log::error!("{}; analytics will not be kept after exit", e);
+        Analytics::in_memory().expect("Failed to initialize in-memory analytics")
+    });
 
     // Initialize Ollama manager
     let ollama = OllamaManager::new();
 
     // Initialize supervisor with config
-    let supervisor = OllamaSupervisor::new(SupervisorConfig::default());
+    let supervisor = OllamaSupervisor::new(SupervisorConfig {
+        default_model: settings.selected_model.clone(),
+        ..SupervisorConfig::default()
+    });
+    supervisor.set_model_requirement(settings.model_requirement());
+    supervisor.set_launch_options(settings.launch_options());
+    supervisor.set_effective_policy(schedule::evaluate(&settings.active_profile(), chrono::Local::now()));
+
+    // Initialize resource governor
+    let governor = ResourceGovernor::new(settings.resource_policy.clone());
+
+    let guardian = Guardian::new(None, settings.guardian_enabled);
+    // PINs set before the marker existed
+    if guardian.is_enabled() && !settings.guardian_enabled {
+        settings.guardian_enabled = true;
+        if let Err(e) = settings.save() {
+            log::error!("Failed to save guardian marker: {}", e);
+        }
+    }
 
     // Create app state
     let app_state = AppState {
         ollama: Arc::new(Mutex::new(ollama)),
-        cache: Arc::new(Mutex::new(cache)),
-        supervisor: Arc::new(Mutex::new(supervisor)),
+        cache,
+        analytics,
+        supervisor: Arc::new(supervisor),
+        governor: Arc::new(governor),
         settings: Arc::new(Mutex::new(settings)),
+        bridge: Arc::new(BridgeEvents::new()),
+        guardian: Arc::new(guardian),
+        managed,
     };
 
     // Clone references for async tasks
     let supervisor_for_bridge = app_state.supervisor.clone();
-    let _settings_for_bridge = app_state.settings.clone();
+    let governor_for_bridge = app_state.governor.clone();
+    let settings_for_bridge = app_state.settings.clone();
+    let events_for_bridge = app_state.bridge.clone();
 
     tauri::Builder::default()
         .manage(app_state)
@@ -366,13 +1103,19 @@ pub fn run() {
             let supervisor_clone = state.supervisor.clone();
             let settings_clone = state.settings.clone();
 
+            // Forward supervisor events to the UI and persist pinned digests
+            let app_handle = app.handle().clone();
+            let settings_for_events = settings_clone.clone();
+            supervisor_clone.set_listener(Arc::new(move |event| {
+                handle_supervisor_event(&app_handle, &settings_for_events, event);
+            }));
+
             // Start Ollama automatically on app launch
             let supervisor_for_start = supervisor_clone.clone();
             tauri::async_runtime::spawn(async move {
-                let supervisor = supervisor_for_start.lock().await;
-                if OllamaSupervisor::is_installed() {
+                if supervisor_for_start.is_installed() {
                     log::info!("Auto-starting Ollama...");
-                    if let Err(e) = supervisor.start().await {
+                    if let Err(e) = supervisor_for_start.start().await {
                         log::error!("Failed to auto-start Ollama: {}", e);
                     }
                 } else {
@@ -380,28 +1123,55 @@ pub fn run() {
                 }
             });
 
-            // Start health monitoring
+            // Start health monitoring on the shared supervisor
             let supervisor_for_monitor = supervisor_clone.clone();
             tauri::async_runtime::spawn(async move {
                 // Small delay to let initial start complete
                 tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
-                let supervisor = supervisor_for_monitor.lock().await;
-                let supervisor_arc = Arc::new(OllamaSupervisor::new(SupervisorConfig::default()));
-                // Note: In production, we'd want to share the same supervisor instance
-                // For now, start a new monitor
-                drop(supervisor);
-                OllamaSupervisor::start_health_monitor(supervisor_arc);
+                OllamaSupervisor::start_health_monitor(supervisor_for_monitor);
             });
 
-            // Start extension bridge server
-            let first_run_complete = Arc::new(Mutex::new(
-                tauri::async_runtime::block_on(async {
-                    settings_clone.lock().await.first_run_complete
-                }),
-            ));
+            // Start resource governor
+            let governor_app = app.handle().clone();
+            state.governor.set_listener(Arc::new(move |status| {
+                let _ = governor_app.emit("governor-status", status);
+            }));
+            let governor_for_monitor = state.governor.clone();
+            let supervisor_for_governor = supervisor_clone.clone();
+            tauri::async_runtime::spawn(async move {
+                governor_for_monitor.start_monitor(supervisor_for_governor);
+            });
+
+            // Write batched cache stats and prune the cache periodically
+            let cache_for_tasks = state.cache.clone();
+            tauri::async_runtime::spawn(async move {
+                cache_for_tasks.start_stats_flush();
+                cache_for_tasks.start_pruning();
+            });
+
+            // Write the weekly digest on its schedule
+            tauri::async_runtime::spawn(run_digest_schedule(app.handle().clone()));
+
+            // Apply profile schedule windows as they start and end
+            tauri::async_runtime::spawn(run_policy_schedule(app.handle().clone()));
+
+            // Reload settings edited outside the app
+            let app_for_watcher = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                AppSettings::start_watcher(move |reloaded| {
+                    tauri::async_runtime::spawn(reload_settings(app_for_watcher.clone(), reloaded));
+                });
+            });
 
+            // Start extension bridge server
             tauri::async_runtime::spawn(async move {
-                extension_bridge::start_bridge_server(supervisor_for_bridge, first_run_complete).await;
+                extension_bridge::start_bridge_server(
+                    supervisor_for_bridge,
+                    governor_for_bridge,
+                    settings_for_bridge,
+                    events_for_bridge,
+                )
+                .await;
             });
 
             // Handle window close - minimize to tray instead of quitting
@@ -433,56 +1203,195 @@ pub fn run() {
             check_ollama_installed,
             get_ollama_status,
             get_friendly_status,
+            get_inference_health,
+            get_governor_status,
+            run_inference_check,
             start_ollama,
             stop_ollama,
             restart_ollama,
             list_ollama_models,
             get_recommended_models,
             pull_model,
+            get_model_availability,
+            repin_model,
+            get_model_pull_status,
             // Settings
             get_settings,
             save_settings,
             set_language,
             complete_first_run,
+            set_active_profile,
+            get_effective_policy,
+            get_action_presets,
             get_autostart_enabled,
             set_autostart_enabled,
+            get_effective_settings,
+            export_settings,
+            import_settings,
+            // Guardian
+            get_guardian_status,
+            set_guardian_pin,
+            clear_guardian_pin,
+            reset_guardian_pin,
+            unlock_guardian,
+            lock_guardian,
+            quit_app,
             // Helpers
             open_ollama_download,
+            install_ollama,
             open_extension_store,
             get_setup_status,
             // Neutralization
             neutralize_content,
+            // Analytics
+            get_analytics,
+            clear_analytics,
+            // Reports
+            generate_digest,
+            open_latest_digest,
             // Cache
             get_cache_stats,
+            get_cache_usage,
+            search_history,
             clear_cache,
+            invalidate_cache,
+            export_cache,
+            import_cache,
+            get_cache_encryption_status,
+            enable_cache_encryption,
+            unlock_cache,
+            lock_cache,
+            rotate_cache_key,
+            disable_cache_encryption,
+            wipe_cache,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
 
-/// Setup system tray with menu
-fn setup_system_tray(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>> {
+/// Forward a supervisor event to the UI and apply its side effects
+fn handle_supervisor_event(app: &AppHandle, settings: &Arc<Mutex<AppSettings>>, event: SupervisorEvent) {
+    if let SupervisorEvent::ModelPinned { model, digest } = &event {
+        let settings = settings.clone();
+        let (model, digest) = (model.clone(), digest.clone());
+        tauri::async_runtime::spawn(async move {
+            let mut settings = settings.lock().await;
+            if settings.selected_model == model && settings.selected_model_digest.as_ref() != Some(&digest) {
+                settings.selected_model_digest = Some(digest);
+                if let Err(e) = settings.save() {
+                    log::error!("Failed to persist model digest: {}", e);
+                }
+            }
+        });
+    }
+
+    let _ = app.emit("supervisor-event", event);
+}
+
+/// ID of the tray icon, for updating its tooltip later
+const TRAY_ID: &str = "main";
+
+/// Prefix of tray menu ids that switch profiles
+const PROFILE_MENU_PREFIX: &str = "profile:";
+
+/// Tray menu, with a switcher listing every profile
+fn tray_menu(app: &AppHandle, settings: &AppSettings) -> tauri::Result<Menu<tauri::Wry>> {
     let show_item = MenuItem::with_id(app, "show", "Show FeelingWise", true, None::<&str>)?;
     let separator1 = MenuItem::with_id(app, "sep1", "─────────────", false, None::<&str>)?;
     let status_item = MenuItem::with_id(app, "status", "● Protected", false, None::<&str>)?;
     let restart_item = MenuItem::with_id(app, "restart", "Restart Protection", true, None::<&str>)?;
+    let report_item = MenuItem::with_id(app, "report", "Open Weekly Report", true, None::<&str>)?;
     let separator2 = MenuItem::with_id(app, "sep2", "─────────────", false, None::<&str>)?;
     let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
 
-    let menu = Menu::with_items(
+    let active = settings.active_profile();
+    let profile_items = settings
+        .profiles
+        .iter()
+        .map(|profile| {
+            CheckMenuItem::with_id(
+                app,
+                format!("{}{}", PROFILE_MENU_PREFIX, profile.id),
+                &profile.name,
+                true,
+                profile.id == active.id,
+                None::<&str>,
+            )
+        })
+        .collect::<tauri::Result<Vec<_>>>()?;
+    let profile_refs: Vec<&dyn IsMenuItem<tauri::Wry>> =
+        profile_items.iter().map(|item| item as &dyn IsMenuItem<tauri::Wry>).collect();
+    let profile_menu = Submenu::with_items(app, format!("Profile: {}", active.name), true, &profile_refs)?;
+
+    Menu::with_items(
         app,
         &[
             &show_item,
             &separator1,
             &status_item,
+            &profile_menu,
             &restart_item,
+            &report_item,
             &separator2,
             &quit_item,
         ],
-    )?;
+    )
+}
+
+/// Rebuild the tray menu after profiles or the active profile changed
+fn refresh_tray_menu(app: &AppHandle, settings: &AppSettings) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    match tray_menu(app, settings) {
+        Ok(menu) => {
+            let _ = tray.set_menu(Some(menu));
+        }
+        Err(e) => log::error!("Failed to rebuild tray menu: {}", e),
+    }
+}
+
+fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
 
-    let _tray = TrayIconBuilder::new()
+/// Switch profiles from the tray. With a guardian PIN set, the window asks
+/// for it and calls `set_active_profile` instead.
+fn switch_profile_from_tray(app: &AppHandle, id: String) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let Some(state) = app.try_state::<AppState>() else {
+            return;
+        };
+
+        if state.guardian.is_enabled() {
+            show_main_window(&app);
+            let _ = app.emit("guardian-required", format!("switch-profile:{}", id));
+            // Put back the check mark the click moved
+            let settings = state.settings.lock().await.clone();
+            refresh_tray_menu(&app, &settings);
+            return;
+        }
+
+        if let Err(e) = activate_profile(&app, &state, &id).await {
+            log::error!("Failed to switch profile: {}", e);
+        }
+    });
+}
+
+/// Setup system tray with menu
+fn setup_system_tray(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>> {
+    let settings = tauri::async_runtime::block_on(async {
+        app.state::<AppState>().settings.lock().await.clone()
+    });
+    let menu = tray_menu(app.handle(), &settings)?;
+
+    let _tray = TrayIconBuilder::with_id(TRAY_ID)
         .icon(app.default_window_icon().unwrap().clone())
+        .tooltip("FeelingWise")
         .menu(&menu)
         .show_menu_on_left_click(false)
         .on_menu_event(|app, event| match event.id.as_ref() {
@@ -496,26 +1405,35 @@ fn setup_system_tray(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Err
                 let app_handle = app.clone();
                 tauri::async_runtime::spawn(async move {
                     if let Some(state) = app_handle.try_state::<AppState>() {
-                        let supervisor = state.supervisor.lock().await;
-                        supervisor.reset_restart_count();
-                        if let Err(e) = supervisor.restart().await {
+                        state.supervisor.reset_restart_count();
+                        if let Err(e) = state.supervisor.restart().await {
                             log::error!("Failed to restart Ollama: {}", e);
                         }
                     }
                 });
             }
+            "report" => {
+                if let Err(e) = open_latest_report(app) {
+                    log::warn!("{}", e);
+                }
+            }
             "quit" => {
-                // Stop Ollama before quitting
-                let app_handle = app.clone();
-                tauri::async_runtime::spawn(async move {
-                    if let Some(state) = app_handle.try_state::<AppState>() {
-                        let supervisor = state.supervisor.lock().await;
-                        let _ = supervisor.stop().await;
-                    }
-                    std::process::exit(0);
-                });
+                // With a guardian PIN set, the window asks for it and calls quit_app
+                let locked = app
+                    .try_state::<AppState>()
+                    .is_some_and(|state| state.guardian.is_enabled());
+                if locked {
+                    show_main_window(app);
+                    let _ = app.emit("guardian-required", "quit");
+                } else {
+                    shutdown(app.clone());
+                }
+            }
+            id => {
+                if let Some(profile_id) = id.strip_prefix(PROFILE_MENU_PREFIX) {
+                    switch_profile_from_tray(app, profile_id.to_string());
+                }
             }
-            _ => {}
         })
         .on_tray_icon_event(|tray, event| {
             if let TrayIconEvent::Click {
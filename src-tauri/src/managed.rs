@@ -0,0 +1,481 @@
+//! Managed Settings Module
+//!
+//! For schools and IT admins deploying one configuration to many machines:
+//! settings can be exported to a signed file and imported elsewhere, and a
+//! read-only managed policy file in the system config directory overrides
+//! user settings and locks those fields.
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use zeroize::Zeroizing;
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::OsRng;
+
+use crate::profiles;
+use crate::settings::AppSettings;
+
+const EXPORT_FORMAT: &str = "feelingwise-settings";
+const EXPORT_VERSION: u32 = 1;
+
+/// Fields that describe this machine rather than how it protects; never
+/// exported, and kept as they are on import
+const LOCAL_FIELDS: &[&str] = &[
+    "first_run_complete",
+    "guardian_enabled",
+    "selected_model_digest",
+    "ollama_binary_path",
+    "ollama_models_dir",
+];
+
+// ============================================================================
+// MANAGED POLICY
+// ============================================================================
+
+/// Read-only policy installed by an administrator
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ManagedPolicy {
+    /// Settings fields forced on every user, by name. Objects replace the
+    /// user's value as a whole.
+    pub settings: Map<String, Value>,
+
+    /// Public keys (hex) of other installs whose exports may be imported;
+    /// this install's own exports are always accepted
+    pub trusted_signers: Vec<String>,
+
+    /// Where the policy was read from
+    #[serde(skip)]
+    pub path: PathBuf,
+}
+
+impl ManagedPolicy {
+    /// Policy file in the system-wide config directory, which only
+    /// administrators can write
+    pub fn default_path() -> PathBuf {
+        #[cfg(target_os = "windows")]
+        {
+            let program_data = std::env::var_os("ProgramData")
+                .map(PathBuf::from)
+                .unwrap_or_else(|| PathBuf::from(r"C:\ProgramData"));
+            program_data.join("FeelingWise").join("policy.json")
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            PathBuf::from("/Library/Application Support/FeelingWise/policy.json")
+        }
+
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        {
+            PathBuf::from("/etc/feelingwise/policy.json")
+        }
+    }
+
+    /// Load the policy at the default path, if one is installed
+    pub fn load() -> Option<Self> {
+        match Self::load_from(&Self::default_path()) {
+            Ok(policy) => policy,
+            Err(e) => {
+                log::error!("{}", e);
+                None
+            }
+        }
+    }
+
+    /// Load the policy at `path`. Fields that don't exist or don't fit the
+    /// settings are dropped with a warning; the rest still apply.
+    pub fn load_from(path: &Path) -> Result<Option<Self>, String> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("Failed to read managed policy: {}", e)),
+        };
+        let mut policy: Self = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse managed policy: {}", e))?;
+        policy.path = path.to_path_buf();
+
+        let defaults = settings_map(&AppSettings::default());
+        let fields = std::mem::take(&mut policy.settings);
+        for (key, value) in fields {
+            let fits = key != "schema_version"
+                && defaults.contains_key(&key)
+                && policy.applied_to_defaults(&key, &value);
+            if fits {
+                policy.settings.insert(key, value);
+            } else {
+                log::warn!("Ignoring managed policy field \"{}\"", key);
+            }
+        }
+
+        log::info!(
+            "Loaded managed policy from {:?}, locking {}",
+            path,
+            policy.settings.keys().cloned().collect::<Vec<_>>().join(", ")
+        );
+        Ok(Some(policy))
+    }
+
+    /// Whether the fields accepted so far plus `key` still make valid settings
+    fn applied_to_defaults(&self, key: &str, value: &Value) -> bool {
+        let mut fields = settings_map(&AppSettings::default());
+        fields.extend(self.settings.clone());
+        fields.insert(key.to_string(), value.clone());
+        serde_json::from_value::<AppSettings>(Value::Object(fields))
+            .is_ok_and(|settings| profiles::validate(&settings.profiles).is_ok())
+    }
+
+    pub fn locks(&self, field: &str) -> bool {
+        self.settings.contains_key(field)
+    }
+
+    /// Force the policy's fields onto `settings`. Returns the fields that
+    /// had a different value.
+    pub fn apply(&self, settings: &mut AppSettings) -> Vec<String> {
+        let mut fields = settings_map(settings);
+        let changed: Vec<String> = self
+            .settings
+            .iter()
+            .filter(|(key, value)| fields.get(*key) != Some(value))
+            .map(|(key, _)| key.clone())
+            .collect();
+        if changed.is_empty() {
+            return changed;
+        }
+
+        fields.extend(self.settings.clone());
+        match serde_json::from_value(Value::Object(fields)) {
+            Ok(applied) => {
+                *settings = applied;
+                changed
+            }
+            Err(e) => {
+                log::error!("Failed to apply managed policy: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    fn trusts(&self, signer: &str) -> bool {
+        self.trusted_signers.iter().any(|key| key.eq_ignore_ascii_case(signer))
+    }
+}
+
+fn settings_map(settings: &AppSettings) -> Map<String, Value> {
+    match serde_json::to_value(settings) {
+        Ok(Value::Object(fields)) => fields,
+        _ => Map::new(),
+    }
+}
+
+/// Where an effective setting's value comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SettingSource {
+    Default,
+    User,
+    /// Set by the managed policy; the UI shows it locked
+    Policy,
+}
+
+/// Settings in force with the source of each field
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EffectiveSettings {
+    pub settings: AppSettings,
+    pub sources: BTreeMap<String, SettingSource>,
+    /// Managed policy file in force, if any
+    pub policy_path: Option<PathBuf>,
+}
+
+impl EffectiveSettings {
+    pub fn new(settings: &AppSettings, policy: Option<&ManagedPolicy>) -> Self {
+        let defaults = settings_map(&AppSettings::default());
+        let sources = settings_map(settings)
+            .into_iter()
+            .map(|(key, value)| {
+                let source = if policy.is_some_and(|p| p.locks(&key)) {
+                    SettingSource::Policy
+                } else if defaults.get(&key) == Some(&value) {
+                    SettingSource::Default
+                } else {
+                    SettingSource::User
+                };
+                (key, source)
+            })
+            .collect();
+
+        Self {
+            settings: settings.clone(),
+            sources,
+            policy_path: policy.map(|p| p.path.clone()),
+        }
+    }
+}
+
+// ============================================================================
+// SIGNED EXPORT AND IMPORT
+// ============================================================================
+
+/// On-disk export: the signed payload is kept as the exact JSON text that
+/// was signed, so verification never depends on key order or formatting
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SignedSettingsFile {
+    format: String,
+    version: u32,
+    /// JSON text of [`ExportPayload`]
+    payload: String,
+    /// Ed25519 public key of the exporting install, hex
+    public_key: String,
+    /// Ed25519 signature over `payload`, hex
+    signature: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ExportPayload {
+    exported_at: i64,
+    app_version: String,
+    settings: Map<String, Value>,
+}
+
+/// Result of a successful import
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportedSettings {
+    pub settings: AppSettings,
+    /// Public key (hex) that signed the file
+    pub signer: String,
+    pub exported_at: i64,
+    /// Fields that couldn't be read and kept their defaults
+    pub dropped: Vec<String>,
+}
+
+/// This install's signing key, if it has made one
+fn saved_signing_key(path: &Path) -> Result<Option<SigningKey>, String> {
+    let Ok(hex_key) = fs::read_to_string(path) else {
+        return Ok(None);
+    };
+    let bytes = Zeroizing::new(hex::decode(hex_key.trim()).map_err(|_| "Corrupt export signing key")?);
+    let bytes: [u8; 32] = bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| "Corrupt export signing key")?;
+    Ok(Some(SigningKey::from_bytes(&bytes)))
+}
+
+/// Key this install signs exports with, created on first use
+fn signing_key(path: &Path) -> Result<SigningKey, String> {
+    if let Some(key) = saved_signing_key(path)? {
+        return Ok(key);
+    }
+
+    let mut bytes = Zeroizing::new([0u8; 32]);
+    OsRng.fill_bytes(bytes.as_mut());
+    let key = SigningKey::from_bytes(&bytes);
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create data directory: {}", e))?;
+    }
+    let encoded = Zeroizing::new(hex::encode(bytes.as_ref()));
+    crate::settings::write_atomically(path, encoded.as_bytes())
+        .map_err(|e| format!("Failed to save export signing key: {}", e))?;
+    Ok(key)
+}
+
+fn default_key_path() -> PathBuf {
+    directories::ProjectDirs::from("com", "feelingwise", "FeelingWise")
+        .map(|dirs| dirs.data_dir().join("export-signing.key"))
+        .unwrap_or_else(|| PathBuf::from("export-signing.key"))
+}
+
+/// Write `settings` to a signed file at `path`. Returns the signer's
+/// public key, which admins list in `trusted_signers`.
+pub fn export_settings(settings: &AppSettings, path: &Path, key_path: Option<&Path>) -> Result<String, String> {
+    let key = signing_key(key_path.unwrap_or(&default_key_path()))?;
+
+    let mut fields = settings_map(settings);
+    for field in LOCAL_FIELDS {
+        fields.remove(*field);
+    }
+    let payload = serde_json::to_string(&ExportPayload {
+        exported_at: chrono::Local::now().timestamp(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        settings: fields,
+    })
+    .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+
+    let public_key = hex::encode(key.verifying_key().as_bytes());
+    let file = SignedSettingsFile {
+        format: EXPORT_FORMAT.to_string(),
+        version: EXPORT_VERSION,
+        signature: hex::encode(key.sign(payload.as_bytes()).to_bytes()),
+        payload,
+        public_key: public_key.clone(),
+    };
+    let json = serde_json::to_string_pretty(&file)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    fs::write(path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    log::info!("Exported settings to {:?}", path);
+    Ok(public_key)
+}
+
+/// Read and verify a signed settings file. Only files signed by this
+/// install or by a key in the policy's `trusted_signers` are accepted.
+/// Fields that describe this machine are taken from `current`.
+pub fn import_settings(
+    path: &Path,
+    current: &AppSettings,
+    policy: Option<&ManagedPolicy>,
+    key_path: Option<&Path>,
+) -> Result<ImportedSettings, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let file: SignedSettingsFile = serde_json::from_str(&content)
+        .map_err(|_| "Not a FeelingWise settings file".to_string())?;
+    if file.format != EXPORT_FORMAT {
+        return Err("Not a FeelingWise settings file".to_string());
+    }
+    if file.version != EXPORT_VERSION {
+        return Err(format!("Unsupported settings file version {}", file.version));
+    }
+
+    let public_key: [u8; 32] = hex::decode(&file.public_key)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or("Settings file has an invalid public key")?;
+    let signature: [u8; 64] = hex::decode(&file.signature)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or("Settings file has an invalid signature")?;
+    VerifyingKey::from_bytes(&public_key)
+        .and_then(|key| key.verify(file.payload.as_bytes(), &Signature::from_bytes(&signature)))
+        .map_err(|_| "Settings file signature doesn't match; it was changed after export".to_string())?;
+
+    let signer = file.public_key.to_lowercase();
+    let own = saved_signing_key(key_path.unwrap_or(&default_key_path()))?
+        .map(|key| hex::encode(key.verifying_key().as_bytes()));
+    if own.as_deref() != Some(signer.as_str()) && !policy.is_some_and(|p| p.trusts(&signer)) {
+        return Err("Settings file wasn't signed by this computer or a trusted administrator".to_string());
+    }
+
+    let payload: ExportPayload = serde_json::from_str(&file.payload)
+        .map_err(|e| format!("Failed to parse settings file: {}", e))?;
+    let mut fields = payload.settings;
+    let local = settings_map(current);
+    for field in LOCAL_FIELDS {
+        match local.get(*field) {
+            Some(value) => fields.insert(field.to_string(), value.clone()),
+            None => fields.remove(*field),
+        };
+    }
+
+    let (settings, dropped) = AppSettings::from_json(fields)?;
+    profiles::validate(&settings.profiles)?;
+
+    Ok(ImportedSettings {
+        settings,
+        signer,
+        exported_at: payload.exported_at,
+        dropped,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profiles::Profile;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("fw-managed-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_signed_export_round_trip() {
+        let dir = temp_dir();
+        let (file, key) = (dir.join("settings.fw.json"), dir.join("signing.key"));
+
+        let mut settings = AppSettings::default();
+        settings.profiles.push(Profile::for_persona("kid", "Ana", "child"));
+        settings.ollama_models_dir = Some("/exporter/models".to_string());
+        let signer = export_settings(&settings, &file, Some(&key)).unwrap();
+
+        let current = AppSettings {
+            ollama_models_dir: Some("/importer/models".to_string()),
+            ..AppSettings::default()
+        };
+        let imported = import_settings(&file, &current, None, Some(&key)).unwrap();
+        assert_eq!(imported.signer, signer);
+        assert_eq!(imported.settings.profiles.len(), 2);
+        assert_eq!(imported.settings.ollama_models_dir.as_deref(), Some("/importer/models"));
+
+        // Another install's export needs its key listed in the policy
+        let other_key = dir.join("other.key");
+        let empty = ManagedPolicy::default();
+        assert!(import_settings(&file, &current, None, Some(&other_key)).unwrap_err().contains("trusted"));
+        assert!(import_settings(&file, &current, Some(&empty), Some(&other_key)).unwrap_err().contains("trusted"));
+        assert!(!other_key.exists());
+        let policy = ManagedPolicy { trusted_signers: vec![signer.to_uppercase()], ..Default::default() };
+        assert!(import_settings(&file, &current, Some(&policy), Some(&other_key)).is_ok());
+
+        // Any edit breaks the signature
+        let tampered = fs::read_to_string(&file).unwrap().replace("Ana", "Bob");
+        fs::write(&file, tampered).unwrap();
+        assert!(import_settings(&file, &current, None, Some(&key)).unwrap_err().contains("signature"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_import_rejects_unknown_signer_without_policy() {
+        let dir = temp_dir();
+        let (file, own_key) = (dir.join("settings.fw.json"), dir.join("signing.key"));
+        export_settings(&AppSettings::default(), &file, Some(&own_key)).unwrap();
+
+        // A file signed with a fresh key verifies, but nobody vouches for it
+        let forged = dir.join("forged.fw.json");
+        export_settings(&AppSettings::default(), &forged, Some(&dir.join("random.key"))).unwrap();
+        let err = import_settings(&forged, &AppSettings::default(), None, Some(&own_key)).unwrap_err();
+        assert!(err.contains("trusted"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_policy_overrides_and_locks_fields() {
+        let dir = temp_dir();
+        let path = dir.join("policy.json");
+        fs::write(&path, r#"{
+            "settings": {
+                "start_on_login": true,
+                "selected_model": "llama3:8b",
+                "cache_policy": "not a policy",
+                "no_such_field": 1
+            }
+        }"#).unwrap();
+
+        let policy = ManagedPolicy::load_from(&path).unwrap().unwrap();
+        assert!(policy.locks("selected_model"));
+        assert!(!policy.locks("cache_policy"));
+        assert!(!policy.locks("no_such_field"));
+
+        let mut settings = AppSettings { start_on_login: false, language: "ro".to_string(), ..Default::default() };
+        let mut changed = policy.apply(&mut settings);
+        changed.sort();
+        assert_eq!(changed, vec!["selected_model", "start_on_login"]);
+        assert!(settings.start_on_login);
+        assert!(policy.apply(&mut settings).is_empty());
+
+        let effective = EffectiveSettings::new(&settings, Some(&policy));
+        assert_eq!(effective.sources["selected_model"], SettingSource::Policy);
+        assert_eq!(effective.sources["language"], SettingSource::User);
+        assert_eq!(effective.sources["digest"], SettingSource::Default);
+
+        assert!(ManagedPolicy::load_from(&dir.join("missing.json")).unwrap().is_none());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
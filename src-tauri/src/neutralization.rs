@@ -0,0 +1,188 @@
+//! Neutralization Module
+//!
+//! Prompt construction and response parsing shared by the neutralization
+//! command and the supervisor's canary health check.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// System prompt for content neutralization (from MASTER_BLUEPRINT)
+pub const NEUTRALIZATION_PROMPT: &str = r#"You are a content neutralization system. Your task is to transform emotionally manipulative social media text into neutral, factual language while preserving ALL original meaning and claims.
+
+## RULES (MUST FOLLOW):
+
+1. PRESERVE the author's viewpoint, concern, topic, and all factual claims
+2. REMOVE only manipulation techniques:
+   - ALL CAPS → normal case
+   - Excessive punctuation (!!!) → single punctuation
+   - Urgency language → factual timeline if applicable
+   - Fear appeals → neutral concern statement
+   - Ad hominem attacks → position-focused language
+   - Absolute language (everyone, always, never) → proportional (some, often, rarely)
+   - Alarm emojis (🚨🔥⚠️) → removed
+
+3. DO NOT:
+   - Add information not in the original
+   - Judge whether claims are true or false
+   - Use loaded verbs (claimed, alleged, admitted, revealed)
+   - Add warnings, disclaimers, or editorial comments
+   - Change the meaning or direction of the opinion
+
+4. OUTPUT FORMAT:
+   Return JSON with the following structure:
+   {
+     "neutralized": "The neutralized version of the text",
+     "techniques": ["List", "of", "detected", "techniques"],
+     "severity": 0-10
+   }
+
+Now neutralize this text:
+"#;
+
+/// Version of the prompt template, derived from its text so any edit to
+/// `NEUTRALIZATION_PROMPT` invalidates results cached under the old one
+pub fn prompt_version() -> String {
+    let digest = Sha256::digest(NEUTRALIZATION_PROMPT.as_bytes());
+    hex::encode(&digest[..6])
+}
+
+/// Parsed model output for a single neutralization
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NeutralizationOutput {
+    pub neutralized: String,
+    pub techniques: Vec<String>,
+    pub severity: i32,
+}
+
+/// Build the full prompt for a piece of content
+pub fn build_prompt(content: &str) -> String {
+    format!("{}{}", NEUTRALIZATION_PROMPT, content)
+}
+
+/// Emojis the prompt asks the model to strip
+const ALARM_EMOJIS: &[char] = &['🚨', '🔥', '⚠', '❗', '‼', '😱'];
+
+/// Rule-based neutralization used when local AI is unavailable or paused.
+///
+/// Mirrors the extension's fallback: tames ALL CAPS words, collapses
+/// repeated punctuation and strips alarm emojis.
+pub fn rule_based(content: &str) -> NeutralizationOutput {
+    let mut techniques = Vec::new();
+
+    // Words of four or more capital letters; shorter ones are usually acronyms
+    let mut caps_found = false;
+    let words: Vec<String> = content
+        .split(' ')
+        .map(|word| {
+            let letters: Vec<char> = word.chars().filter(|c| c.is_alphabetic()).collect();
+            if letters.len() >= 4 && letters.iter().all(|c| c.is_uppercase()) {
+                caps_found = true;
+                let lower = word.to_lowercase();
+                let mut chars = lower.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_uppercase().chain(chars).collect())
+                    .unwrap_or_default()
+            } else {
+                word.to_string()
+            }
+        })
+        .collect();
+    if caps_found {
+        techniques.push("All Caps".to_string());
+    }
+
+    let mut neutralized = String::with_capacity(content.len());
+    let mut punctuation_found = false;
+    let mut emoji_found = false;
+    let mut previous: Option<char> = None;
+    for c in words.join(" ").chars() {
+        // U+FE0F is the emoji presentation selector that trails some emojis
+        if c == '\u{FE0F}' {
+            continue;
+        }
+        if ALARM_EMOJIS.contains(&c) {
+            emoji_found = true;
+            continue;
+        }
+        if (c == '!' || c == '?') && previous == Some(c) {
+            punctuation_found = true;
+            continue;
+        }
+        neutralized.push(c);
+        previous = Some(c);
+    }
+    if punctuation_found {
+        techniques.push("Excessive Punctuation".to_string());
+    }
+    if emoji_found {
+        techniques.push("Alarm Emojis".to_string());
+    }
+
+    let neutralized = neutralized.split_whitespace().collect::<Vec<_>>().join(" ");
+    let severity = (techniques.len() as i32 * 2).min(10);
+
+    NeutralizationOutput {
+        neutralized,
+        techniques,
+        severity,
+    }
+}
+
+/// Parse the JSON returned by the model into a neutralization result
+pub fn parse_response(response: &str) -> Result<NeutralizationOutput, String> {
+    let parsed: serde_json::Value = serde_json::from_str(response)
+        .map_err(|e| format!("Failed to parse AI response: {}. Response: {}", e, response))?;
+
+    let neutralized = parsed["neutralized"]
+        .as_str()
+        .ok_or("Missing 'neutralized' field in response")?
+        .to_string();
+
+    let techniques: Vec<String> = parsed["techniques"]
+        .as_array()
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let severity = parsed["severity"].as_i64().unwrap_or(0) as i32;
+
+    Ok(NeutralizationOutput {
+        neutralized,
+        techniques,
+        severity,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rule_based_neutralization() {
+        let output = rule_based("🚨 URGENT!!! They are LYING to you???");
+        assert_eq!(output.neutralized, "Urgent! They are Lying to you?");
+        assert_eq!(
+            output.techniques,
+            vec!["All Caps", "Excessive Punctuation", "Alarm Emojis"]
+        );
+        assert_eq!(output.severity, 6);
+    }
+
+    #[test]
+    fn test_prompt_version_is_stable() {
+        assert_eq!(prompt_version(), prompt_version());
+        assert_eq!(prompt_version().len(), 12);
+    }
+
+    #[test]
+    fn test_rule_based_leaves_calm_text_alone() {
+        let output = rule_based("The meeting is at 5pm, see the FAQ.");
+        assert_eq!(output.neutralized, "The meeting is at 5pm, see the FAQ.");
+        assert!(output.techniques.is_empty());
+        assert_eq!(output.severity, 0);
+    }
+}
@@ -1,19 +1,17 @@
+//! Ollama API Client
+//!
+//! Thin HTTP client for the Ollama REST API. Process lifecycle lives in the
+//! supervisor; this module never spawns or kills anything.
+
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
-use std::process::{Child, Command, Stdio};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tokio::time::{sleep, Duration};
 
-#[cfg(target_os = "windows")]
-use std::os::windows::process::CommandExt;
-
-#[cfg(target_os = "windows")]
-const CREATE_NO_WINDOW: u32 = 0x08000000;
-
-const OLLAMA_API_BASE: &str = "http://127.0.0.1:11434";
-const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+pub const OLLAMA_API_BASE: &str = "http://127.0.0.1:11434";
+const GENERATE_TIMEOUT: Duration = Duration::from_secs(60);
+const LIST_TIMEOUT: Duration = Duration::from_secs(5);
 const MAX_RETRIES: u32 = 3;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +19,8 @@ pub struct ModelInfo {
     pub name: String,
     pub size: u64,
     pub modified_at: String,
+    #[serde(default)]
+    pub digest: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -68,6 +68,28 @@ pub struct PullProgress {
     pub completed: Option<u64>,
 }
 
+/// One line of a streamed pull: progress, nothing (blank), or the error
+/// Ollama reported
+fn parse_pull_line(line: &[u8]) -> Result<Option<PullProgress>, String> {
+    let line = String::from_utf8_lossy(line);
+    let line = line.trim();
+    if line.is_empty() {
+        return Ok(None);
+    }
+
+    let value: serde_json::Value = match serde_json::from_str(line) {
+        Ok(value) => value,
+        Err(_) => return Ok(None),
+    };
+    if let Some(error) = value.get("error") {
+        return Err(format!(
+            "Model pull failed: {}",
+            error.as_str().map(str::to_string).unwrap_or_else(|| error.to_string())
+        ));
+    }
+    Ok(serde_json::from_value(value).ok())
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OllamaStatus {
     pub running: bool,
@@ -77,7 +99,6 @@ pub struct OllamaStatus {
 
 pub struct OllamaManager {
     client: Client,
-    process: Arc<Mutex<Option<Child>>>,
     current_model: Arc<Mutex<Option<String>>>,
 }
 
@@ -85,7 +106,6 @@ impl OllamaManager {
     pub fn new() -> Self {
         Self {
             client: Client::new(),
-            process: Arc::new(Mutex::new(None)),
             current_model: Arc::new(Mutex::new(None)),
         }
     }
@@ -102,149 +122,11 @@ impl OllamaManager {
         }
     }
 
-    /// Check if Ollama is installed on the system
-    pub fn is_ollama_installed() -> bool {
-        Self::find_ollama_binary().is_some()
-    }
-
-    /// Find Ollama binary across common installation paths
-    pub fn find_ollama_binary() -> Option<PathBuf> {
-        #[cfg(target_os = "windows")]
-        {
-            // Check common Windows installation paths
-            let possible_paths = [
-                // User-specific installation (most common for Ollama)
-                dirs::data_local_dir()
-                    .map(|p| p.join("Programs").join("Ollama").join("ollama.exe")),
-                // Alternative user path
-                dirs::home_dir()
-                    .map(|p| p.join("AppData").join("Local").join("Programs").join("Ollama").join("ollama.exe")),
-                // System-wide installation
-                Some(PathBuf::from(r"C:\Program Files\Ollama\ollama.exe")),
-            ];
-
-            for path in possible_paths.iter().flatten() {
-                if path.exists() {
-                    log::info!("Found Ollama at: {:?}", path);
-                    return Some(path.clone());
-                }
-            }
-
-            // Check PATH as fallback
-            if let Ok(output) = Command::new("where")
-                .arg("ollama")
-                .stdout(Stdio::piped())
-                .stderr(Stdio::null())
-                .output()
-            {
-                if output.status.success() {
-                    let path_str = String::from_utf8_lossy(&output.stdout);
-                    if let Some(first_line) = path_str.lines().next() {
-                        let path = PathBuf::from(first_line.trim());
-                        if path.exists() {
-                            log::info!("Found Ollama in PATH: {:?}", path);
-                            return Some(path);
-                        }
-                    }
-                }
-            }
-
-            None
-        }
-
-        #[cfg(not(target_os = "windows"))]
-        {
-            // Check common Unix paths
-            let possible_paths = [
-                "/usr/local/bin/ollama",
-                "/usr/bin/ollama",
-                "/opt/ollama/ollama",
-            ];
-
-            for path_str in &possible_paths {
-                let path = PathBuf::from(path_str);
-                if path.exists() {
-                    return Some(path);
-                }
-            }
-
-            // Check PATH as fallback
-            if let Ok(output) = Command::new("which")
-                .arg("ollama")
-                .stdout(Stdio::piped())
-                .stderr(Stdio::null())
-                .output()
-            {
-                if output.status.success() {
-                    let path_str = String::from_utf8_lossy(&output.stdout);
-                    let path = PathBuf::from(path_str.trim());
-                    if path.exists() {
-                        return Some(path);
-                    }
-                }
-            }
-
-            None
-        }
-    }
-
-    /// Start Ollama server with CORS enabled for browser extension
-    pub async fn start(&self) -> Result<(), String> {
-        // Check if already running
-        if self.is_healthy().await {
-            log::info!("Ollama is already running");
-            return Ok(());
-        }
-
-        // Find Ollama binary
-        let ollama_path = Self::find_ollama_binary()
-            .ok_or_else(|| "Ollama is not installed. Please install it from https://ollama.ai".to_string())?;
-
-        log::info!("Starting Ollama from: {:?}", ollama_path);
-
-        // Build command with CORS enabled for browser extension
-        let mut cmd = Command::new(&ollama_path);
-        cmd.arg("serve")
-            .env("OLLAMA_ORIGINS", "*") // Enable CORS for browser extension
-            .env("OLLAMA_HOST", "127.0.0.1:11434")
-            .stdout(Stdio::null())
-            .stderr(Stdio::null());
-
-        // Hide console window on Windows
-        #[cfg(target_os = "windows")]
-        cmd.creation_flags(CREATE_NO_WINDOW);
-
-        let child = cmd
-            .spawn()
-            .map_err(|e| format!("Failed to start Ollama: {}", e))?;
-
-        *self.process.lock().await = Some(child);
-
-        // Wait for Ollama to be ready (up to 30 seconds)
-        for i in 0..60 {
-            sleep(Duration::from_millis(500)).await;
-            if self.is_healthy().await {
-                log::info!("Ollama started successfully after {}ms", (i + 1) * 500);
-                return Ok(());
-            }
-        }
-
-        Err("Ollama failed to start within 30 seconds".to_string())
-    }
-
-    /// Stop Ollama server
-    pub async fn stop(&self) -> Result<(), String> {
-        let mut process = self.process.lock().await;
-        if let Some(mut child) = process.take() {
-            child.kill().map_err(|e| format!("Failed to stop Ollama: {}", e))?;
-        }
-        Ok(())
-    }
-
     /// List available models
     pub async fn list_models(&self) -> Result<Vec<ModelInfo>, String> {
         let response = self.client
             .get(format!("{}/api/tags", OLLAMA_API_BASE))
+            .timeout(LIST_TIMEOUT)
             .send()
             .await
             .map_err(|e| format!("Failed to list models: {}", e))?;
@@ -260,7 +142,8 @@ impl OllamaManager {
         Ok(tags.models.unwrap_or_default())
     }
 
-    /// Pull a model from Ollama registry
+    /// Pull a model from Ollama registry. Succeeds only once Ollama reports
+    /// `"status": "success"`; an error status or error line fails the pull.
     pub async fn pull_model<F>(&self, model_name: &str, progress_callback: F) -> Result<(), String>
     where
         F: Fn(PullProgress) + Send + 'static,
@@ -275,25 +158,41 @@ impl OllamaManager {
             .await
             .map_err(|e| format!("Failed to pull model: {}", e))?;
 
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(format!("Failed to pull model ({}): {}", status, body.trim()));
+        }
+
         let mut stream = response.bytes_stream();
         use futures_util::StreamExt;
 
+        // Lines can be split across chunks
+        let mut pending: Vec<u8> = Vec::new();
+        let mut succeeded = false;
+        let mut handle_line = |line: &[u8]| -> Result<(), String> {
+            if let Some(progress) = parse_pull_line(line)? {
+                succeeded |= progress.status == "success";
+                progress_callback(progress);
+            }
+            Ok(())
+        };
+
         while let Some(chunk) = stream.next().await {
-            match chunk {
-                Ok(bytes) => {
-                    if let Ok(text) = String::from_utf8(bytes.to_vec()) {
-                        for line in text.lines() {
-                            if let Ok(progress) = serde_json::from_str::<PullProgress>(line) {
-                                progress_callback(progress);
-                            }
-                        }
-                    }
-                }
-                Err(e) => return Err(format!("Error during model pull: {}", e)),
+            let bytes = chunk.map_err(|e| format!("Error during model pull: {}", e))?;
+            pending.extend_from_slice(&bytes);
+            while let Some(end) = pending.iter().position(|b| *b == b'\n') {
+                let line: Vec<u8> = pending.drain(..=end).collect();
+                handle_line(&line)?;
             }
         }
+        handle_line(&pending)?;
 
-        Ok(())
+        if succeeded {
+            Ok(())
+        } else {
+            Err(format!("Pull of {} ended without success", model_name))
+        }
     }
 
     /// Generate completion from Ollama
@@ -310,13 +209,7 @@ impl OllamaManager {
 
         let mut retries = 0;
         loop {
-            match self.client
-                .post(format!("{}/api/generate", OLLAMA_API_BASE))
-                .json(&request)
-                .timeout(Duration::from_secs(60))
-                .send()
-                .await
-            {
+            match self.send_generate(&request, GENERATE_TIMEOUT).await {
                 Ok(response) => {
                     let gen_response: GenerateResponse = response.json().await
                         .map_err(|e| format!("Failed to parse generate response: {}", e))?;
@@ -336,6 +229,59 @@ impl OllamaManager {
         }
     }
 
+    /// Ask Ollama to drop a model from memory right away
+    pub async fn unload_model(&self, model: &str) -> Result<(), String> {
+        let response = self.client
+            .post(format!("{}/api/generate", OLLAMA_API_BASE))
+            .json(&serde_json::json!({
+                "model": model,
+                "keep_alive": 0
+            }))
+            .timeout(LIST_TIMEOUT)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to unload model: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("Unload request returned {}", response.status()));
+        }
+        Ok(())
+    }
+
+    /// Run a single generate request without retries
+    pub async fn generate_once(
+        &self,
+        request: &GenerateRequest,
+        timeout: Duration,
+    ) -> Result<GenerateResponse, String> {
+        let response = self
+            .send_generate(request, timeout)
+            .await
+            .map_err(|e| format!("Generate request failed: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("Generate request returned {}", response.status()));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse generate response: {}", e))
+    }
+
+    async fn send_generate(
+        &self,
+        request: &GenerateRequest,
+        timeout: Duration,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        self.client
+            .post(format!("{}/api/generate", OLLAMA_API_BASE))
+            .json(request)
+            .timeout(timeout)
+            .send()
+            .await
+    }
+
     /// Get current status
     pub async fn get_status(&self) -> OllamaStatus {
         let running = self.is_healthy().await;
@@ -404,3 +350,21 @@ pub fn get_recommended_models() -> Vec<RecommendedModel> {
         },
     ]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pull_line() {
+        let progress = parse_pull_line(br#"{"status":"pulling abc","total":10,"completed":4}"#)
+            .unwrap()
+            .unwrap();
+        assert_eq!((progress.total, progress.completed), (Some(10), Some(4)));
+        assert_eq!(parse_pull_line(b"{\"status\":\"success\"}\n").unwrap().unwrap().status, "success");
+        assert!(parse_pull_line(b"  \n").unwrap().is_none());
+        assert!(parse_pull_line(br#"{"error":"pull model manifest: file does not exist"}"#)
+            .unwrap_err()
+            .contains("does not exist"));
+    }
+}
@@ -0,0 +1,187 @@
+//! Profiles Module
+//!
+//! Families share one machine, so settings hold several profiles, each with
+//! its own persona, severity threshold and protected platforms. One profile
+//! is active at a time: its persona keys the cache, the extension reads it
+//! from the bridge, and its id is recorded on analytics events. A profile's
+//! schedule can change its policy at set times, see [`crate::schedule`]; its
+//! severity threshold is applied when each result's action is decided, see
+//! [`crate::actions`].
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::actions::ActionPolicy;
+use crate::schedule::ScheduleWindow;
+
+/// Id of the profile created for settings from before profiles existed
+pub const DEFAULT_PROFILE_ID: &str = "default";
+
+/// Personas the app writes explanations for
+pub const PERSONAS: &[&str] = &["child", "teen", "adult"];
+
+/// One person's protection settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    /// Stable id, used by the tray menu and recorded on events
+    pub id: String,
+
+    /// Name shown in the app and the tray
+    pub name: String,
+
+    /// "child", "teen" or "adult"; sets the tone of explanations
+    pub persona: String,
+
+    /// Content scored below this severity (0-10) is shown unchanged
+    pub severity_threshold: i32,
+
+    /// Platforms protected under this profile; empty means all of them
+    pub platforms: Vec<String>,
+
+    /// Weekly windows that override this profile; the first in force wins
+    pub schedule: Vec<ScheduleWindow>,
+
+    /// Custom action rules; without them the preset for the persona in
+    /// force applies
+    pub actions: Option<ActionPolicy>,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self::for_persona(DEFAULT_PROFILE_ID, "Default", "adult")
+    }
+}
+
+impl Profile {
+    /// A profile with the threshold suited to `persona`
+    pub fn for_persona(id: &str, name: &str, persona: &str) -> Self {
+        let severity_threshold = match persona {
+            "child" => 1,
+            "teen" => 3,
+            _ => 5,
+        };
+
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            persona: persona.to_string(),
+            severity_threshold,
+            platforms: Vec::new(),
+            schedule: Vec::new(),
+            actions: None,
+        }
+    }
+
+    /// Whether content from `platform` is protected; unknown platforms always are
+    pub fn covers_platform(&self, platform: &str) -> bool {
+        covers_platform(&self.platforms, platform)
+    }
+}
+
+/// Whether `platforms` (empty meaning all) protects content from `platform`
+pub fn covers_platform(platforms: &[String], platform: &str) -> bool {
+    platform.is_empty()
+        || platforms.is_empty()
+        || platforms.iter().any(|p| p.eq_ignore_ascii_case(platform))
+}
+
+/// Check a profile list before it replaces the saved one
+pub fn validate(profiles: &[Profile]) -> Result<(), String> {
+    if profiles.is_empty() {
+        return Err("At least one profile is required".to_string());
+    }
+
+    let mut ids = HashSet::new();
+    for profile in profiles {
+        if profile.id.trim().is_empty() {
+            return Err(format!("Profile \"{}\" needs an id", profile.name));
+        }
+        if !ids.insert(profile.id.as_str()) {
+            return Err(format!("Duplicate profile id \"{}\"", profile.id));
+        }
+        if profile.name.trim().is_empty() {
+            return Err(format!("Profile \"{}\" needs a name", profile.id));
+        }
+        if !PERSONAS.contains(&profile.persona.as_str()) {
+            return Err(format!("Unknown persona \"{}\"", profile.persona));
+        }
+        if !(0..=10).contains(&profile.severity_threshold) {
+            return Err(format!(
+                "Severity threshold for \"{}\" must be between 0 and 10",
+                profile.name
+            ));
+        }
+        for window in &profile.schedule {
+            window.validate()?;
+        }
+        if let Some(actions) = &profile.actions {
+            actions.validate()?;
+        }
+    }
+    Ok(())
+}
+
+/// The profile with id `active`, or the first one if it's gone
+pub fn resolve(profiles: &[Profile], active: &str) -> Profile {
+    profiles
+        .iter()
+        .find(|p| p.id == active)
+        .or_else(|| profiles.first())
+        .cloned()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_and_resolve() {
+        let mut profiles = vec![
+            Profile::for_persona("kid", "Ana", "child"),
+            Profile {
+                platforms: vec!["twitter".to_string()],
+                ..Profile::for_persona("parent", "Dan", "adult")
+            },
+        ];
+        assert!(validate(&profiles).is_ok());
+        assert!(validate(&[]).is_err());
+
+        assert_eq!(resolve(&profiles, "parent").name, "Dan");
+        assert_eq!(resolve(&profiles, "removed").id, "kid");
+        assert_eq!(resolve(&[], "kid").id, DEFAULT_PROFILE_ID);
+
+        assert!(profiles[0].covers_platform("reddit"));
+        assert!(profiles[1].covers_platform("Twitter"));
+        assert!(!profiles[1].covers_platform("reddit"));
+        assert!(profiles[1].covers_platform(""));
+
+        profiles[1].id = "kid".to_string();
+        assert!(validate(&profiles).unwrap_err().contains("Duplicate"));
+        profiles[1].id = "parent".to_string();
+        profiles[1].persona = "robot".to_string();
+        assert!(validate(&profiles).is_err());
+    }
+
+    #[test]
+    fn test_threshold_and_schedule_apply() {
+        use crate::actions::ContentAction;
+        use crate::schedule::evaluate;
+
+        let json = r#"{"id": "kid", "name": "Ana", "persona": "teen", "severity_threshold": 6,
+                       "schedule": [{"name": "Always", "days": [0, 1, 2, 3, 4, 5, 6],
+                                     "start": "00:00", "end": "00:00", "severity_threshold": 8}]}"#;
+        let mut profile: Profile = serde_json::from_str(json).unwrap();
+        assert_eq!(profile.schedule[0].severity_threshold, Some(8));
+        assert!(validate(std::slice::from_ref(&profile)).is_ok());
+
+        let now = chrono::Local::now();
+        assert_eq!(evaluate(&profile, now).decide(&[], 7, "").action, ContentAction::ShowOriginal);
+
+        profile.schedule.clear();
+        let policy = evaluate(&profile, now);
+        assert_eq!(policy.decide(&[], 5, "").action, ContentAction::ShowOriginal);
+        assert_eq!(policy.decide(&[], 7, "").action, ContentAction::ShowNeutralized);
+    }
+}
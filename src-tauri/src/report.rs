@@ -0,0 +1,508 @@
+//! Weekly Digest Module
+//!
+//! Summarizes the past week for parents who don't open the app: totals, top
+//! techniques, the severity trend and a few before/after examples, written
+//! as HTML and Markdown files into the app data directory.
+
+use crate::analytics::{Analytics, DayTotal, NamedCount};
+use crate::cache::{HistoryQuery, NeutralizationCache};
+use crate::crypto;
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate, Timelike};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Days covered by a digest, today included
+const DIGEST_DAYS: u32 = 7;
+/// Techniques listed in a digest
+const TOP_TECHNIQUES: usize = 5;
+/// Before/after pairs shown in a digest
+const EXAMPLE_COUNT: usize = 3;
+/// Lowest severity worth showing as an example
+const EXAMPLE_MIN_SEVERITY: i32 = 5;
+/// Change in average severity still reported as steady
+const TREND_TOLERANCE: f64 = 0.5;
+
+/// When to write the weekly digest (stored in `AppSettings`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DigestSchedule {
+    pub enabled: bool,
+    /// 0 = Sunday
+    pub weekday: u32,
+    /// Local hour, 0-23
+    pub hour: u32,
+    /// Flag the tray icon when a new digest is ready
+    pub notify: bool,
+}
+
+impl Default for DigestSchedule {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            weekday: 0,
+            hour: 18,
+            notify: true,
+        }
+    }
+}
+
+impl DigestSchedule {
+    /// Local date of the latest scheduled run at or before `now`; `None`
+    /// when the digest is turned off
+    pub fn last_slot(&self, now: DateTime<Local>) -> Option<NaiveDate> {
+        if !self.enabled {
+            return None;
+        }
+        let today = now.date_naive();
+        let mut days_back = (today.weekday().num_days_from_sunday() + 7 - self.weekday % 7) % 7;
+        if days_back == 0 && now.hour() < self.hour {
+            days_back = 7;
+        }
+        today.checked_sub_days(Days::new(days_back as u64))
+    }
+}
+
+/// Average severity compared with the week before
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SeverityTrend {
+    Rising,
+    Falling,
+    Steady,
+    /// Nothing recorded the week before
+    New,
+}
+
+/// A before/after pair from the week
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DigestExample {
+    pub original: String,
+    pub neutralized: String,
+    pub techniques: Vec<String>,
+    pub severity: i32,
+    pub platform: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeeklyDigest {
+    pub generated_at: i64,
+    /// First local day covered (`YYYY-MM-DD`)
+    pub period_start: String,
+    /// Last local day covered, normally today
+    pub period_end: String,
+    pub total_events: i64,
+    pub average_severity: f64,
+    pub previous_average_severity: Option<f64>,
+    pub trend: SeverityTrend,
+    pub top_techniques: Vec<NamedCount>,
+    /// Days with activity, oldest first
+    pub daily: Vec<DayTotal>,
+    pub examples: Vec<DigestExample>,
+    /// Examples are left out while the cache is encrypted, so the report
+    /// never holds plaintext the cache is protecting
+    pub examples_withheld: bool,
+}
+
+/// Where a digest was written
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DigestFiles {
+    pub html: PathBuf,
+    pub markdown: PathBuf,
+}
+
+impl WeeklyDigest {
+    /// Build a digest of the last seven days from analytics and the cache
+    pub fn build(analytics: &Analytics, cache: &NeutralizationCache) -> Result<Self, String> {
+        let now = Local::now();
+        let today = now.date_naive();
+        let start = today - Days::new(DIGEST_DAYS as u64 - 1);
+        let period_start = start.format("%Y-%m-%d").to_string();
+
+        let summary = analytics.summary(Some(DIGEST_DAYS))?;
+        let (daily, previous): (Vec<DayTotal>, Vec<DayTotal>) = analytics
+            .daily_trend(DIGEST_DAYS * 2)?
+            .into_iter()
+            .partition(|day| day.day >= period_start);
+
+        let previous_events: i64 = previous.iter().map(|d| d.events).sum();
+        let previous_average_severity = (previous_events > 0).then(|| {
+            previous.iter().map(|d| d.average_severity * d.events as f64).sum::<f64>()
+                / previous_events as f64
+        });
+        let trend = match previous_average_severity {
+            None => SeverityTrend::New,
+            Some(prev) if summary.average_severity > prev + TREND_TOLERANCE => SeverityTrend::Rising,
+            Some(prev) if summary.average_severity < prev - TREND_TOLERANCE => SeverityTrend::Falling,
+            Some(_) => SeverityTrend::Steady,
+        };
+
+        let examples_withheld = cache.encryption_status().enabled;
+        let examples = if examples_withheld {
+            Vec::new()
+        } else {
+            let from = start
+                .and_hms_opt(0, 0, 0)
+                .and_then(|midnight| midnight.and_local_timezone(Local).earliest())
+                .map(|midnight| midnight.timestamp());
+            let mut candidates: Vec<_> = cache
+                .history(&HistoryQuery {
+                    from,
+                    min_severity: Some(EXAMPLE_MIN_SEVERITY),
+                    limit: 50,
+                    ..Default::default()
+                })?
+                .items
+                .into_iter()
+                // Privacy mode keeps no originals to show
+                .filter(|entry| !entry.original.is_empty() && entry.original != entry.neutralized)
+                .collect();
+            candidates.sort_by_key(|entry| std::cmp::Reverse(entry.severity));
+            candidates
+                .into_iter()
+                .take(EXAMPLE_COUNT)
+                .map(|entry| DigestExample {
+                    original: entry.original,
+                    neutralized: entry.neutralized,
+                    techniques: entry.techniques,
+                    severity: entry.severity,
+                    platform: entry.source.platform,
+                })
+                .collect()
+        };
+
+        Ok(Self {
+            generated_at: now.timestamp(),
+            period_start,
+            period_end: today.format("%Y-%m-%d").to_string(),
+            total_events: summary.total_events,
+            average_severity: summary.average_severity,
+            previous_average_severity,
+            trend,
+            top_techniques: summary.technique_totals.into_iter().take(TOP_TECHNIQUES).collect(),
+            daily,
+            examples,
+            examples_withheld,
+        })
+    }
+
+    /// One-line description of the severity trend
+    fn trend_sentence(&self) -> String {
+        match (self.trend, self.previous_average_severity) {
+            (SeverityTrend::Rising, Some(prev)) => format!("up from {:.1} the week before", prev),
+            (SeverityTrend::Falling, Some(prev)) => format!("down from {:.1} the week before", prev),
+            (SeverityTrend::Steady, _) => "about the same as the week before".to_string(),
+            _ => "no data for the week before".to_string(),
+        }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::new();
+        md.push_str("# FeelingWise weekly digest\n\n");
+        md.push_str(&format!("**{} to {}**\n\n", self.period_start, self.period_end));
+        md.push_str(&format!("- Posts checked: {}\n", self.total_events));
+        md.push_str(&format!(
+            "- Average severity: {:.1} / 10, {}\n\n",
+            self.average_severity,
+            self.trend_sentence()
+        ));
+
+        md.push_str("## Top techniques\n\n");
+        if self.top_techniques.is_empty() {
+            md.push_str("No manipulation techniques were detected this week.\n\n");
+        } else {
+            for (i, technique) in self.top_techniques.iter().enumerate() {
+                md.push_str(&format!("{}. {} ({})\n", i + 1, technique.name, technique.events));
+            }
+            md.push('\n');
+        }
+
+        md.push_str("## Severity by day\n\n");
+        md.push_str("| Day | Posts | Average severity |\n|---|---|---|\n");
+        for day in &self.daily {
+            md.push_str(&format!("| {} | {} | {:.1} |\n", day.day, day.events, day.average_severity));
+        }
+        md.push('\n');
+
+        md.push_str("## Examples\n\n");
+        if self.examples_withheld {
+            md.push_str("Examples are hidden because the cache is encrypted. Open FeelingWise to review them.\n");
+        } else if self.examples.is_empty() {
+            md.push_str("Nothing severe enough to show this week.\n");
+        }
+        for example in &self.examples {
+            md.push_str(&format!("### Severity {}", example.severity));
+            if !example.platform.is_empty() {
+                md.push_str(&format!(" on {}", example.platform));
+            }
+            md.push_str("\n\n");
+            md.push_str(&quote_markdown(&example.original));
+            md.push_str("\n**Neutralized:**\n\n");
+            md.push_str(&quote_markdown(&example.neutralized));
+            md.push('\n');
+            if !example.techniques.is_empty() {
+                md.push_str(&format!("_Techniques: {}_\n\n", example.techniques.join(", ")));
+            }
+        }
+        md
+    }
+
+    pub fn to_html(&self) -> String {
+        let mut body = String::new();
+        body.push_str(&format!(
+            "<h1>FeelingWise weekly digest</h1>\n<p class=\"period\">{} to {}</p>\n",
+            escape_html(&self.period_start),
+            escape_html(&self.period_end)
+        ));
+        body.push_str(&format!(
+            "<ul>\n<li>Posts checked: {}</li>\n<li>Average severity: {:.1} / 10, {}</li>\n</ul>\n",
+            self.total_events,
+            self.average_severity,
+            escape_html(&self.trend_sentence())
+        ));
+
+        body.push_str("<h2>Top techniques</h2>\n");
+        if self.top_techniques.is_empty() {
+            body.push_str("<p>No manipulation techniques were detected this week.</p>\n");
+        } else {
+            body.push_str("<ol>\n");
+            for technique in &self.top_techniques {
+                body.push_str(&format!(
+                    "<li>{} ({})</li>\n",
+                    escape_html(&technique.name),
+                    technique.events
+                ));
+            }
+            body.push_str("</ol>\n");
+        }
+
+        body.push_str("<h2>Severity by day</h2>\n<table>\n<tr><th>Day</th><th>Posts</th><th>Average severity</th></tr>\n");
+        for day in &self.daily {
+            body.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{:.1}</td></tr>\n",
+                escape_html(&day.day),
+                day.events,
+                day.average_severity
+            ));
+        }
+        body.push_str("</table>\n");
+
+        body.push_str("<h2>Examples</h2>\n");
+        if self.examples_withheld {
+            body.push_str("<p>Examples are hidden because the cache is encrypted. Open FeelingWise to review them.</p>\n");
+        } else if self.examples.is_empty() {
+            body.push_str("<p>Nothing severe enough to show this week.</p>\n");
+        }
+        for example in &self.examples {
+            let platform = if example.platform.is_empty() {
+                String::new()
+            } else {
+                format!(" on {}", escape_html(&example.platform))
+            };
+            body.push_str(&format!(
+                "<div class=\"example\">\n<h3>Severity {}{}</h3>\n<blockquote>{}</blockquote>\n<p><strong>Neutralized:</strong> {}</p>\n",
+                example.severity,
+                platform,
+                escape_html(&example.original),
+                escape_html(&example.neutralized)
+            ));
+            if !example.techniques.is_empty() {
+                body.push_str(&format!(
+                    "<p class=\"techniques\">Techniques: {}</p>\n",
+                    escape_html(&example.techniques.join(", "))
+                ));
+            }
+            body.push_str("</div>\n");
+        }
+
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>FeelingWise weekly digest</title>\n<style>\n{}\n</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+            DIGEST_CSS, body
+        )
+    }
+
+    /// Write `digest-<day>.html` and `digest-<day>.md` into `dir`
+    pub fn write(&self, dir: &Path, day: &str) -> Result<DigestFiles, String> {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create reports directory: {}", e))?;
+
+        let files = DigestFiles {
+            html: dir.join(format!("digest-{}.html", day)),
+            markdown: dir.join(format!("digest-{}.md", day)),
+        };
+        fs::write(&files.html, self.to_html())
+            .and_then(|_| fs::write(&files.markdown, self.to_markdown()))
+            .map_err(|e| format!("Failed to write digest: {}", e))?;
+
+        log::info!("Wrote weekly digest to {:?}", files.html);
+        Ok(files)
+    }
+}
+
+const DIGEST_CSS: &str = "body { font-family: system-ui, sans-serif; max-width: 40rem; margin: 2rem auto; color: #1f2937; }
+.period { color: #6b7280; }
+table { border-collapse: collapse; }
+th, td { padding: 0.25rem 0.75rem; border-bottom: 1px solid #e5e7eb; text-align: left; }
+blockquote { margin: 0; padding: 0.5rem 1rem; border-left: 4px solid #f87171; background: #fef2f2; }
+.example { margin-bottom: 1.5rem; }
+.techniques { color: #6b7280; font-size: 0.9rem; }";
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Quote `text` as a Markdown blockquote, one line per source line, with
+/// anything Markdown would read as formatting escaped
+fn quote_markdown(text: &str) -> String {
+    let mut quoted = String::new();
+    for line in text.lines().map(str::trim_start) {
+        if line.is_empty() {
+            quoted.push_str(">\n");
+            continue;
+        }
+        // Markers that only count at the start of a line: headings, lists,
+        // nested quotes, setext underlines and `1.` / `1)` items
+        let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        let marker = match line[digits..].chars().next() {
+            Some('.' | ')') if digits > 0 => Some(digits),
+            Some('#' | '-' | '+' | '=' | '>') if digits == 0 => Some(0),
+            _ => None,
+        };
+
+        quoted.push_str("> ");
+        for (i, c) in line.char_indices() {
+            if Some(i) == marker || "\\`*_[]<>|~&".contains(c) {
+                quoted.push('\\');
+            }
+            quoted.push(c);
+        }
+        quoted.push('\n');
+    }
+    quoted
+}
+
+/// Directory digests are written to
+pub fn reports_dir() -> PathBuf {
+    directories::ProjectDirs::from("com", "feelingwise", "FeelingWise")
+        .map(|dirs| dirs.data_dir().join("reports"))
+        .unwrap_or_else(|| PathBuf::from("reports"))
+}
+
+/// Whether a digest for `day` has been written
+pub fn digest_exists(dir: &Path, day: &str) -> bool {
+    dir.join(format!("digest-{}.html", day)).exists()
+}
+
+/// Newest HTML digest in `dir`
+pub fn latest_digest(dir: &Path) -> Option<PathBuf> {
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("digest-") && name.ends_with(".html"))
+        })
+        .max()
+}
+
+/// Overwrite and remove every digest in `dir`. Digests quote cached
+/// originals, so they go when those are encrypted, scrubbed or wiped.
+pub fn wipe_digests(dir: &Path) -> Result<usize, String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(0);
+    };
+    let mut wiped = 0;
+    for path in entries.filter_map(|entry| entry.ok().map(|e| e.path())) {
+        let is_digest = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with("digest-") && (name.ends_with(".html") || name.ends_with(".md")));
+        if is_digest {
+            crypto::wipe_file(&path)?;
+            wiped += 1;
+        }
+    }
+    Ok(wiped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytics::{EventSource, NeutralizationEvent};
+    use crate::cache::{CacheKey, ContentSource};
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_last_slot() {
+        let schedule = DigestSchedule { enabled: true, weekday: 0, hour: 18, ..Default::default() };
+        // 2026-10-18 is a Sunday
+        let sunday_noon = Local.with_ymd_and_hms(2026, 10, 18, 12, 0, 0).unwrap();
+        let sunday_evening = Local.with_ymd_and_hms(2026, 10, 18, 19, 0, 0).unwrap();
+        let tuesday = Local.with_ymd_and_hms(2026, 10, 20, 9, 0, 0).unwrap();
+
+        assert_eq!(schedule.last_slot(sunday_noon), NaiveDate::from_ymd_opt(2026, 10, 11));
+        assert_eq!(schedule.last_slot(sunday_evening), NaiveDate::from_ymd_opt(2026, 10, 18));
+        assert_eq!(schedule.last_slot(tuesday), NaiveDate::from_ymd_opt(2026, 10, 18));
+        assert_eq!(DigestSchedule::default().last_slot(tuesday), None);
+    }
+
+    #[test]
+    fn test_quote_markdown() {
+        assert_eq!(
+            quote_markdown("# Not a heading\n\n- *them* [link](x) | a_b\n2. done\n   > nested"),
+            "> \\# Not a heading\n>\n> \\- \\*them\\* \\[link\\](x) \\| a\\_b\n> 2\\. done\n> \\> nested\n"
+        );
+    }
+
+    #[test]
+    fn test_build_and_render_digest() {
+        let analytics = Analytics::in_memory().unwrap();
+        let cache = NeutralizationCache::in_memory().unwrap();
+        let key = CacheKey {
+            model: "phi3:mini".to_string(),
+            prompt_version: "v1".to_string(),
+            persona: "adult".to_string(),
+        };
+        let techniques = vec!["fear_appeal".to_string()];
+        let source = ContentSource { platform: "twitter".to_string(), ..Default::default() };
+        cache.set("They <b>WILL</b> take EVERYTHING!!!", &key, "Some worry about a policy.", &techniques, 8, &source).unwrap();
+        analytics.record(&NeutralizationEvent {
+            timestamp: Local::now().timestamp(),
+            platform: "twitter".to_string(),
+            techniques,
+            severity: 8,
+            latency_ms: 900,
+            model: "phi3:mini".to_string(),
+            source: EventSource::Model,
+            profile: "default".to_string(),
+        }).unwrap();
+
+        let digest = WeeklyDigest::build(&analytics, &cache).unwrap();
+        assert_eq!((digest.total_events, digest.trend), (1, SeverityTrend::New));
+        assert_eq!(digest.examples.len(), 1);
+
+        let html = digest.to_html();
+        assert!(html.contains("&lt;b&gt;WILL&lt;/b&gt;"));
+        assert!(!html.contains("<b>WILL"));
+        let md = digest.to_markdown();
+        assert!(md.contains("1. fear_appeal (1)"));
+        assert!(md.contains("> They \\<b\\>WILL\\</b\\> take EVERYTHING!!!\n"));
+        assert!(md.contains("**Neutralized:**\n\n> Some worry about a policy.\n"));
+
+        let dir = std::env::temp_dir().join(format!("fw-reports-{}", uuid::Uuid::new_v4()));
+        let files = digest.write(&dir, &digest.period_end).unwrap();
+        assert!(digest_exists(&dir, &digest.period_end));
+        assert_eq!(latest_digest(&dir), Some(files.html));
+
+        fs::write(dir.join("notes.md"), "kept").unwrap();
+        assert_eq!(wipe_digests(&dir).unwrap(), 2);
+        assert!(!digest_exists(&dir, &digest.period_end) && !files.markdown.exists());
+        assert!(dir.join("notes.md").exists());
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,243 @@
+//! Schedule Module
+//!
+//! Weekly time windows on a profile that change its policy for part of the
+//! week: a stricter persona during school hours, a lower threshold in the
+//! evening, or no filtering at weekends. Windows use local time.
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+
+use crate::actions::{ActionDecision, ActionPolicy, ContentAction};
+use crate::profiles::{self, Profile, PERSONAS};
+
+/// A weekly window that overrides parts of its profile
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScheduleWindow {
+    /// Shown in the app and reported with the effective policy
+    pub name: String,
+
+    /// Days the window starts on, 0 = Sunday
+    pub days: Vec<u32>,
+
+    /// Local start time, "HH:MM"
+    pub start: String,
+
+    /// Local end time, "HH:MM"; at or before `start` means the next day
+    pub end: String,
+
+    /// Persona used instead of the profile's
+    pub persona: Option<String>,
+
+    /// Severity threshold used instead of the profile's
+    pub severity_threshold: Option<i32>,
+
+    /// Turn protection off for the length of the window
+    pub paused: bool,
+}
+
+impl Default for ScheduleWindow {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            days: vec![1, 2, 3, 4, 5],
+            start: "08:00".to_string(),
+            end: "15:00".to_string(),
+            persona: None,
+            severity_threshold: None,
+            paused: false,
+        }
+    }
+}
+
+impl ScheduleWindow {
+    fn times(&self) -> Result<(NaiveTime, NaiveTime), String> {
+        let parse = |value: &str| {
+            NaiveTime::parse_from_str(value, "%H:%M")
+                .map_err(|_| format!("Schedule \"{}\" has an invalid time \"{}\"", self.name, value))
+        };
+        Ok((parse(&self.start)?, parse(&self.end)?))
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        self.times()?;
+        if self.days.iter().any(|day| *day > 6) {
+            return Err(format!("Schedule \"{}\" has an invalid day", self.name));
+        }
+        if let Some(persona) = &self.persona {
+            if !PERSONAS.contains(&persona.as_str()) {
+                return Err(format!("Unknown persona \"{}\"", persona));
+            }
+        }
+        if let Some(threshold) = self.severity_threshold {
+            if !(0..=10).contains(&threshold) {
+                return Err(format!(
+                    "Severity threshold for schedule \"{}\" must be between 0 and 10",
+                    self.name
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Start and end of every occurrence that starts between the day before
+    /// `around` and a week after it
+    fn occurrences(&self, around: NaiveDate) -> Vec<(NaiveDateTime, NaiveDateTime)> {
+        let Ok((start, end)) = self.times() else {
+            return Vec::new();
+        };
+
+        (-1..=7)
+            .filter_map(|offset| {
+                let date = around + Duration::days(offset);
+                if !self.days.contains(&date.weekday().num_days_from_sunday()) {
+                    return None;
+                }
+                let from = date.and_time(start);
+                let mut to = date.and_time(end);
+                if to <= from {
+                    to += Duration::days(1);
+                }
+                Some((from, to))
+            })
+            .collect()
+    }
+}
+
+/// What protection follows right now: the active profile with any schedule
+/// window in force applied
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EffectivePolicy {
+    pub profile_id: String,
+    pub profile_name: String,
+    pub persona: String,
+    pub severity_threshold: i32,
+    /// Protected platforms; empty means all of them
+    pub platforms: Vec<String>,
+    /// Protection is off until the window ends
+    pub paused: bool,
+    /// Name of the window in force, if any
+    pub window: Option<String>,
+    /// Unix time of the next window start or end, if the profile has windows
+    pub next_change: Option<i64>,
+    /// The profile's action rules, or the preset for `persona`
+    pub actions: ActionPolicy,
+}
+
+impl EffectivePolicy {
+    /// Action for a result from `platform`; content the policy doesn't
+    /// protect right now is shown as is
+    pub fn decide(&self, techniques: &[String], severity: i32, platform: &str) -> ActionDecision {
+        if self.paused || !profiles::covers_platform(&self.platforms, platform) {
+            return ActionDecision { action: ContentAction::ShowOriginal, rule: None };
+        }
+        self.actions.decide(techniques, severity, self.severity_threshold)
+    }
+}
+
+/// Apply the first of `profile`'s windows in force at `now`
+pub fn evaluate(profile: &Profile, now: DateTime<Local>) -> EffectivePolicy {
+    let now = now.naive_local();
+    let today = now.date();
+
+    let active = profile.schedule.iter().find(|window| {
+        window
+            .occurrences(today)
+            .iter()
+            .any(|(from, to)| *from <= now && now < *to)
+    });
+    let persona = active
+        .and_then(|window| window.persona.clone())
+        .unwrap_or_else(|| profile.persona.clone());
+
+    let next_change = profile
+        .schedule
+        .iter()
+        .flat_map(|window| window.occurrences(today))
+        .flat_map(|(from, to)| [from, to])
+        .filter(|boundary| *boundary > now)
+        .min()
+        .and_then(|boundary| Local.from_local_datetime(&boundary).earliest())
+        .map(|boundary| boundary.timestamp());
+
+    EffectivePolicy {
+        profile_id: profile.id.clone(),
+        profile_name: profile.name.clone(),
+        severity_threshold: active
+            .and_then(|window| window.severity_threshold)
+            .unwrap_or(profile.severity_threshold),
+        platforms: profile.platforms.clone(),
+        paused: active.is_some_and(|window| window.paused),
+        window: active.map(|window| window.name.clone()),
+        next_change,
+        actions: profile
+            .actions
+            .clone()
+            .unwrap_or_else(|| ActionPolicy::preset(&persona)),
+        persona,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_windows_override_profile() {
+        let profile = Profile {
+            schedule: vec![
+                ScheduleWindow {
+                    name: "School".to_string(),
+                    persona: Some("child".to_string()),
+                    severity_threshold: Some(1),
+                    ..Default::default()
+                },
+                ScheduleWindow {
+                    name: "Weekend".to_string(),
+                    days: vec![6],
+                    start: "00:00".to_string(),
+                    end: "00:00".to_string(),
+                    paused: true,
+                    ..Default::default()
+                },
+                ScheduleWindow {
+                    name: "Late".to_string(),
+                    days: vec![1],
+                    start: "22:00".to_string(),
+                    end: "02:00".to_string(),
+                    severity_threshold: Some(2),
+                    ..Default::default()
+                },
+            ],
+            ..Profile::for_persona("kid", "Ana", "teen")
+        };
+
+        // 2024-06-03 is a Monday
+        let at = |d: u32, h: u32, m: u32| Local.with_ymd_and_hms(2024, 6, d, h, m, 0).unwrap();
+
+        let school = evaluate(&profile, at(3, 9, 30));
+        assert_eq!((school.persona.as_str(), school.severity_threshold), ("child", 1));
+        assert_eq!(school.window.as_deref(), Some("School"));
+        assert_eq!(school.next_change, Some(at(3, 15, 0).timestamp()));
+        assert_eq!(school.actions, ActionPolicy::preset("child"));
+
+        let evening = evaluate(&profile, at(3, 18, 0));
+        assert_eq!((evening.persona.as_str(), evening.severity_threshold), ("teen", 3));
+        assert_eq!(evening.window, None);
+        assert_eq!(evening.next_change, Some(at(3, 22, 0).timestamp()));
+
+        // Windows that cross midnight belong to the day they start
+        assert_eq!(evaluate(&profile, at(4, 1, 0)).window.as_deref(), Some("Late"));
+        assert_eq!(evaluate(&profile, at(5, 1, 0)).window, None);
+
+        let saturday = evaluate(&profile, at(8, 12, 0));
+        assert!(saturday.paused);
+        let fear = vec!["Fear Appeal".to_string()];
+        assert_eq!(saturday.decide(&fear, 9, "").action, ContentAction::ShowOriginal);
+        assert_eq!(school.decide(&fear, 9, "reddit").action, ContentAction::Hide);
+        assert_eq!(saturday.next_change, Some(at(9, 0, 0).timestamp()));
+
+        assert!(ScheduleWindow { start: "25:00".to_string(), ..Default::default() }.validate().is_err());
+        assert!(ScheduleWindow { days: vec![7], ..Default::default() }.validate().is_err());
+    }
+}
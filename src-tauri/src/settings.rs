@@ -3,12 +3,154 @@
 //! Persistent settings storage for FeelingWise application.
 
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::cache::CachePolicy;
+use crate::governor::ResourcePolicy;
+use crate::profiles::{self, Profile, DEFAULT_PROFILE_ID};
+use crate::report::DigestSchedule;
+use crate::supervisor::{ModelRequirement, OllamaLaunchOptions};
+
+/// How often the settings file is checked for edits made outside the app
+pub const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+/// Longest wait for another instance to finish writing
+const LOCK_TIMEOUT: Duration = Duration::from_secs(3);
+/// A lock older than this was left behind by a crashed writer
+const LOCK_STALE_AFTER: Duration = Duration::from_secs(10);
+
+/// Hash of what this process last read from or wrote to each settings
+/// file, so the watcher only reacts to edits made elsewhere
+fn synced_hashes() -> &'static Mutex<HashMap<PathBuf, Vec<u8>>> {
+    static SYNCED: OnceLock<Mutex<HashMap<PathBuf, Vec<u8>>>> = OnceLock::new();
+    SYNCED.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn mark_synced(path: &Path, contents: &[u8]) {
+    if let Ok(mut synced) = synced_hashes().lock() {
+        synced.insert(path.to_path_buf(), Sha256::digest(contents).to_vec());
+    }
+}
+
+fn is_synced(path: &Path, contents: &[u8]) -> bool {
+    synced_hashes()
+        .lock()
+        .map(|synced| synced.get(path).is_some_and(|hash| *hash == Sha256::digest(contents).to_vec()))
+        .unwrap_or(false)
+}
+
+/// Cross-process lock on the settings file, held while writing. It is a
+/// `settings.json.lock` file created exclusively and removed on drop.
+struct SettingsLock {
+    path: PathBuf,
+}
+
+impl SettingsLock {
+    fn acquire(settings_path: &Path) -> Result<Self, String> {
+        let path = settings_path.with_extension("json.lock");
+        let deadline = Instant::now() + LOCK_TIMEOUT;
+
+        loop {
+            match fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    let _ = write!(file, "{}", std::process::id());
+                    return Ok(Self { path });
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    let stale = fs::metadata(&path)
+                        .and_then(|m| m.modified())
+                        .ok()
+                        .and_then(|modified| modified.elapsed().ok())
+                        .is_some_and(|age| age > LOCK_STALE_AFTER);
+                    if stale {
+                        log::warn!("Removing stale settings lock {:?}", path);
+                        let _ = fs::remove_file(&path);
+                        continue;
+                    }
+                    if Instant::now() >= deadline {
+                        return Err("Settings are being saved by another FeelingWise window".to_string());
+                    }
+                    std::thread::sleep(Duration::from_millis(25));
+                }
+                Err(e) => return Err(format!("Failed to lock settings: {}", e)),
+            }
+        }
+    }
+}
+
+impl Drop for SettingsLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Replace `path` with `contents` so readers see the old or the new file,
+/// never a partial one, even after a crash or power loss
+pub(crate) fn write_atomically(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let tmp = path.with_extension("json.tmp");
+    let mut file = fs::File::create(&tmp)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    drop(file);
+
+    fs::rename(&tmp, path)?;
+
+    // Make the rename itself durable
+    #[cfg(unix)]
+    if let Some(parent) = path.parent() {
+        if let Ok(dir) = fs::File::open(parent) {
+            let _ = dir.sync_all();
+        }
+    }
+    Ok(())
+}
+
+type SettingsMigration = fn(&mut Map<String, Value>);
+
+/// Ordered settings migrations, applied to the raw JSON; entry `i` upgrades
+/// `schema_version` `i` to `i + 1`. Released migrations are never edited,
+/// only appended to.
+const MIGRATIONS: &[SettingsMigration] = &[migrate_v1_versioned, migrate_v2_profiles];
+
+/// Schema version written by this build
+pub const SETTINGS_SCHEMA_VERSION: u32 = MIGRATIONS.len() as u32;
+
+/// v1: files from before versioning. Every field added since then has a
+/// default, so they only gain the version number.
+fn migrate_v1_versioned(_settings: &mut Map<String, Value>) {}
 
-/// Application settings that persist between sessions
+/// v2: the global `persona` moves into a default profile
+fn migrate_v2_profiles(settings: &mut Map<String, Value>) {
+    let persona = settings.remove("persona");
+    if settings.contains_key("profiles") {
+        return;
+    }
+
+    let persona = persona.as_ref().and_then(Value::as_str).unwrap_or("adult");
+    let profile = Profile::for_persona(DEFAULT_PROFILE_ID, "Default", persona);
+    if let Ok(profile) = serde_json::to_value(profile) {
+        settings.insert("profiles".to_string(), Value::Array(vec![profile]));
+        settings.insert("active_profile".to_string(), Value::from(DEFAULT_PROFILE_ID));
+    }
+}
+
+/// Application settings that persist between sessions.
+///
+/// Every field falls back to its default when missing, so a file written by
+/// an older build still loads. Renames and other changes a default can't
+/// cover go in `MIGRATIONS`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AppSettings {
+    /// Format of the settings file, see `MIGRATIONS`
+    pub schema_version: u32,
+
     /// User interface language (e.g., "en", "ro")
     pub language: String,
 
@@ -21,11 +163,39 @@ pub struct AppSettings {
     /// Has the user completed first-run setup
     pub first_run_complete: bool,
 
+    /// A guardian PIN has been set; a missing guardian file then means it
+    /// was removed outside the app. Only the guardian commands change it.
+    pub guardian_enabled: bool,
+
     /// Selected AI model for neutralization
     pub selected_model: String,
 
-    /// User's persona setting for explanations
-    pub persona: String,
+    /// Digest pinned for the selected model on first verification
+    pub selected_model_digest: Option<String>,
+
+    /// Download the selected model automatically when it is missing
+    pub auto_pull_model: bool,
+
+    /// Everyone who uses this machine, see [`profiles`]
+    pub profiles: Vec<Profile>,
+
+    /// Id of the profile protection currently follows
+    pub active_profile: String,
+
+    /// Custom path to the Ollama binary (searched before the defaults)
+    pub ollama_binary_path: Option<String>,
+
+    /// Custom model storage directory, passed to Ollama as OLLAMA_MODELS
+    pub ollama_models_dir: Option<String>,
+
+    /// When to throttle neutralization because the machine is busy or on battery
+    pub resource_policy: ResourcePolicy,
+
+    /// Expiry, size limits and eviction for the neutralization cache
+    pub cache_policy: CachePolicy,
+
+    /// When to write the weekly digest report
+    pub digest: DigestSchedule,
 }
 
 impl Default for AppSettings {
@@ -34,12 +204,22 @@ impl Default for AppSettings {
         let system_lang = Self::detect_system_language();
 
         Self {
+            schema_version: SETTINGS_SCHEMA_VERSION,
             language: system_lang,
             start_on_login: true, // Default to enabled for parent convenience
             minimize_to_tray: true, // Run silently in background
             first_run_complete: false,
+            guardian_enabled: false,
             selected_model: "phi3:mini".to_string(),
-            persona: "adult".to_string(),
+            selected_model_digest: None,
+            auto_pull_model: true,
+            profiles: vec![Profile::default()],
+            active_profile: DEFAULT_PROFILE_ID.to_string(),
+            ollama_binary_path: None,
+            ollama_models_dir: None,
+            resource_policy: ResourcePolicy::default(),
+            cache_policy: CachePolicy::default(),
+            digest: DigestSchedule::default(),
         }
     }
 }
@@ -47,35 +227,143 @@ impl Default for AppSettings {
 impl AppSettings {
     /// Load settings from disk, or create defaults if not found
     pub fn load() -> Self {
-        let path = Self::settings_path();
+        Self::load_from(&Self::settings_path())
+    }
 
-        if path.exists() {
-            match fs::read_to_string(&path) {
-                Ok(content) => match serde_json::from_str(&content) {
-                    Ok(settings) => {
-                        log::info!("Loaded settings from {:?}", path);
-                        return settings;
-                    }
-                    Err(e) => {
-                        log::warn!("Failed to parse settings: {}, using defaults", e);
-                    }
-                },
-                Err(e) => {
-                    log::warn!("Failed to read settings file: {}, using defaults", e);
+    /// Load settings from `path`, migrating older files. A file that can't
+    /// be read as settings is kept as `settings.json.bak` before defaults
+    /// replace it.
+    pub fn load_from(path: &Path) -> Self {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let defaults = Self::default();
+                if let Err(e) = defaults.save_to(path) {
+                    log::warn!("Failed to save default settings: {}", e);
+                }
+                return defaults;
+            }
+            Err(e) => {
+                // Leave the file alone; it may be readable next time
+                log::warn!("Failed to read settings file: {}, using defaults", e);
+                return Self::default();
+            }
+        };
+        mark_synced(path, content.as_bytes());
+
+        let mut fields = match serde_json::from_str::<Value>(&content) {
+            Ok(Value::Object(fields)) => fields,
+            _ => {
+                log::warn!("Settings file is not valid JSON, using defaults");
+                Self::back_up(path);
+                let defaults = Self::default();
+                if let Err(e) = defaults.save_to(path) {
+                    log::warn!("Failed to save default settings: {}", e);
                 }
+                return defaults;
+            }
+        };
+
+        let version = fields
+            .get("schema_version")
+            .and_then(Value::as_u64)
+            .unwrap_or(0) as u32;
+        if version > SETTINGS_SCHEMA_VERSION {
+            // Written by a newer build: read what we understand, and don't
+            // write back an older format over it
+            log::warn!(
+                "Settings schema version {} is newer than supported version {}",
+                version, SETTINGS_SCHEMA_VERSION
+            );
+            return Self::from_fields(fields).0;
+        }
+
+        Self::migrate(&mut fields, version);
+        let (settings, dropped) = Self::from_fields(fields);
+        let changed = version < SETTINGS_SCHEMA_VERSION || !dropped.is_empty();
+        if !dropped.is_empty() {
+            log::warn!("Reset unreadable settings to defaults: {}", dropped.join(", "));
+        }
+        if changed {
+            Self::back_up(path);
+            if let Err(e) = settings.save_to(path) {
+                log::warn!("Failed to save migrated settings: {}", e);
+            }
+        }
+
+        log::info!("Loaded settings from {:?}", path);
+        settings
+    }
+
+    /// Settings from a JSON object written by this or an older build, such
+    /// as an exported file. Returns the fields that fell back to defaults.
+    pub fn from_json(mut fields: Map<String, Value>) -> Result<(Self, Vec<String>), String> {
+        let version = fields
+            .get("schema_version")
+            .and_then(Value::as_u64)
+            .unwrap_or(0) as u32;
+        if version > SETTINGS_SCHEMA_VERSION {
+            return Err(format!(
+                "Settings were written by a newer version of FeelingWise (schema {})",
+                version
+            ));
+        }
+
+        Self::migrate(&mut fields, version);
+        Ok(Self::from_fields(fields))
+    }
+
+    /// Run the migrations after `version` on raw settings fields
+    fn migrate(fields: &mut Map<String, Value>, version: u32) {
+        for (index, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+            migration(fields);
+            log::info!("Migrated settings to schema version {}", index + 1);
+        }
+        fields.insert("schema_version".to_string(), Value::from(SETTINGS_SCHEMA_VERSION));
+    }
+
+    /// Deserialize settings, keeping every field that parses. Returns the
+    /// names of fields that had to fall back to their defaults.
+    fn from_fields(fields: Map<String, Value>) -> (Self, Vec<String>) {
+        if let Ok(settings) = serde_json::from_value(Value::Object(fields.clone())) {
+            return (settings, Vec::new());
+        }
+
+        let mut merged = match serde_json::to_value(Self::default()) {
+            Ok(Value::Object(defaults)) => defaults,
+            _ => return (Self::default(), fields.into_iter().map(|(key, _)| key).collect()),
+        };
+        let mut dropped = Vec::new();
+        for (key, value) in fields {
+            let previous = merged.insert(key.clone(), value);
+            if serde_json::from_value::<Self>(Value::Object(merged.clone())).is_err() {
+                match previous {
+                    Some(previous) => merged.insert(key.clone(), previous),
+                    None => merged.remove(&key),
+                };
+                dropped.push(key);
             }
         }
 
-        let defaults = Self::default();
-        // Try to save defaults
-        let _ = defaults.save();
-        defaults
+        let settings = serde_json::from_value(Value::Object(merged)).unwrap_or_default();
+        (settings, dropped)
+    }
+
+    /// Copy the settings file to `settings.json.bak`, replacing an older copy
+    fn back_up(path: &Path) {
+        let backup = path.with_extension("json.bak");
+        match fs::copy(path, &backup) {
+            Ok(_) => log::info!("Backed up settings to {:?}", backup),
+            Err(e) => log::warn!("Failed to back up settings: {}", e),
+        }
     }
 
     /// Save settings to disk
     pub fn save(&self) -> Result<(), String> {
-        let path = Self::settings_path();
+        self.save_to(&Self::settings_path())
+    }
 
+    fn save_to(&self, path: &Path) -> Result<(), String> {
         // Ensure parent directory exists
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent).map_err(|e| format!("Failed to create settings dir: {}", e))?;
@@ -84,12 +372,48 @@ impl AppSettings {
         let json =
             serde_json::to_string_pretty(self).map_err(|e| format!("Failed to serialize settings: {}", e))?;
 
-        fs::write(&path, json).map_err(|e| format!("Failed to write settings: {}", e))?;
+        let _lock = SettingsLock::acquire(path)?;
+        write_atomically(path, json.as_bytes()).map_err(|e| format!("Failed to write settings: {}", e))?;
+        mark_synced(path, json.as_bytes());
 
         log::info!("Settings saved to {:?}", path);
         Ok(())
     }
 
+    /// Watch the settings file for edits made outside the app, by hand or by
+    /// another instance, and pass the reloaded settings to `on_change`
+    pub fn start_watcher<F>(on_change: F)
+    where
+        F: Fn(AppSettings) + Send + 'static,
+    {
+        let path = Self::settings_path();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(WATCH_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Some(settings) = Self::reload_if_changed(&path) {
+                    on_change(settings);
+                }
+            }
+        });
+    }
+
+    /// Reload `path` if it no longer holds what this process last read or
+    /// wrote. Files that don't parse yet are skipped; an editor may still
+    /// be writing them.
+    fn reload_if_changed(path: &Path) -> Option<Self> {
+        let contents = fs::read(path).ok()?;
+        if is_synced(path, &contents) {
+            return None;
+        }
+        if !matches!(serde_json::from_slice::<Value>(&contents), Ok(Value::Object(_))) {
+            return None;
+        }
+
+        log::info!("Settings changed on disk, reloading");
+        Some(Self::load_from(path))
+    }
+
     /// Get the path to the settings file
     fn settings_path() -> PathBuf {
         let config_dir = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
@@ -115,6 +439,36 @@ impl AppSettings {
         "en".to_string()
     }
 
+    /// The active profile, or the first one if the active id is gone
+    pub fn active_profile(&self) -> Profile {
+        profiles::resolve(&self.profiles, &self.active_profile)
+    }
+
+    /// Model the supervisor should verify and keep available
+    pub fn model_requirement(&self) -> ModelRequirement {
+        ModelRequirement {
+            name: self.selected_model.clone(),
+            digest: self.selected_model_digest.clone(),
+            auto_pull: self.auto_pull_model,
+        }
+    }
+
+    /// How the supervisor should launch `ollama serve`
+    pub fn launch_options(&self) -> OllamaLaunchOptions {
+        let non_empty = |value: &Option<String>| {
+            value
+                .as_deref()
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(PathBuf::from)
+        };
+
+        OllamaLaunchOptions {
+            binary_path: non_empty(&self.ollama_binary_path),
+            models_dir: non_empty(&self.ollama_models_dir),
+        }
+    }
+
     /// Update a single setting and save
     pub fn update<F>(&mut self, updater: F) -> Result<(), String>
     where
@@ -227,4 +581,110 @@ mod tests {
         let parsed: AppSettings = serde_json::from_str(&json).unwrap();
         assert_eq!(settings.language, parsed.language);
     }
+
+    #[test]
+    fn test_model_fields_default_when_missing() {
+        let json = r#"{
+            "language": "en",
+            "start_on_login": true,
+            "minimize_to_tray": true,
+            "first_run_complete": true,
+            "selected_model": "llama3:8b",
+            "persona": "adult"
+        }"#;
+        let parsed: AppSettings = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.selected_model, "llama3:8b");
+        assert!(parsed.selected_model_digest.is_none());
+        assert!(parsed.auto_pull_model);
+    }
+
+    fn temp_settings() -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("fw-settings-{}", uuid::Uuid::new_v4()))
+            .join("settings.json")
+    }
+
+    #[test]
+    fn test_migrates_unversioned_file_and_keeps_values() {
+        let path = temp_settings();
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, r#"{
+            "language": "ro",
+            "first_run_complete": true,
+            "selected_model": "llama3:8b",
+            "persona": "child",
+            "cache_policy": "not a policy",
+            "some_future_field": 42
+        }"#).unwrap();
+
+        let settings = AppSettings::load_from(&path);
+        assert_eq!(settings.schema_version, SETTINGS_SCHEMA_VERSION);
+        assert_eq!(settings.language, "ro");
+        assert!(settings.first_run_complete);
+        assert_eq!(settings.selected_model, "llama3:8b");
+        assert_eq!(settings.cache_policy.ttl_hours, CachePolicy::default().ttl_hours);
+        assert_eq!(settings.active_profile().persona, "child");
+        assert_eq!(settings.active_profile().severity_threshold, 1);
+
+        // The original is kept and the migrated file loads cleanly
+        assert!(path.with_extension("json.bak").exists());
+        let reloaded: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(reloaded["schema_version"], SETTINGS_SCHEMA_VERSION);
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_backs_up_unparseable_file() {
+        let path = temp_settings();
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "{ \"language\": \"ro\", ").unwrap();
+
+        let settings = AppSettings::load_from(&path);
+        assert!(!settings.first_run_complete);
+        assert_eq!(
+            fs::read_to_string(path.with_extension("json.bak")).unwrap(),
+            "{ \"language\": \"ro\", "
+        );
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_save_is_locked_and_watcher_sees_outside_edits() {
+        let path = temp_settings();
+        let settings = AppSettings::load_from(&path);
+        assert!(AppSettings::reload_if_changed(&path).is_none());
+
+        // Another instance holding the lock makes the save wait, then fail
+        let lock = SettingsLock::acquire(&path).unwrap();
+        assert!(settings.save_to(&path).is_err());
+        drop(lock);
+        settings.save_to(&path).unwrap();
+        assert!(!path.with_extension("json.tmp").exists());
+        assert!(AppSettings::reload_if_changed(&path).is_none());
+
+        // Half-written edits wait; complete ones are picked up once
+        fs::write(&path, "{ \"language\": ").unwrap();
+        assert!(AppSettings::reload_if_changed(&path).is_none());
+        let mut edited: Value = serde_json::to_value(&settings).unwrap();
+        edited["language"] = Value::from("ro");
+        fs::write(&path, serde_json::to_string_pretty(&edited).unwrap()).unwrap();
+        assert_eq!(AppSettings::reload_if_changed(&path).unwrap().language, "ro");
+        assert!(AppSettings::reload_if_changed(&path).is_none());
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_launch_options_ignore_blank_paths() {
+        let settings = AppSettings {
+            ollama_binary_path: Some("  ".to_string()),
+            ollama_models_dir: Some("/data/ollama-models".to_string()),
+            ..AppSettings::default()
+        };
+        let options = settings.launch_options();
+        assert!(options.binary_path.is_none());
+        assert_eq!(options.models_dir, Some(PathBuf::from("/data/ollama-models")));
+    }
 }
@@ -3,24 +3,40 @@
 //! Manages the Ollama process lifecycle with automatic health monitoring,
 //! crash recovery, and user-friendly status reporting.
 
+use chrono::Local;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Stdio};
 use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::Mutex;
 use tokio::time::{sleep, Duration};
 
+use crate::installer;
+use crate::neutralization::{self, NeutralizationOutput};
+use crate::ollama::{GenerateOptions, GenerateRequest, OllamaManager, PullProgress};
+use crate::profiles::Profile;
+use crate::schedule::{self, EffectivePolicy};
+
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
 
 #[cfg(target_os = "windows")]
 const CREATE_NO_WINDOW: u32 = 0x08000000;
 
-const OLLAMA_API_BASE: &str = "http://127.0.0.1:11434";
 const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(5);
 const MAX_RESTART_ATTEMPTS: u32 = 3;
 const STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+const CANARY_INTERVAL: Duration = Duration::from_secs(300);
+const CANARY_TIMEOUT: Duration = Duration::from_secs(60);
+const MAX_CANARY_FAILURES: u32 = 3;
+/// Wait after the first failed background pull; doubles with each failure
+const PULL_BACKOFF_BASE: Duration = Duration::from_secs(30);
+const PULL_BACKOFF_MAX: Duration = Duration::from_secs(60 * 60);
+
+/// Fixed text neutralized by the canary check
+const CANARY_TEXT: &str = "BREAKING!!! EVERYONE needs to see this RIGHT NOW 🚨";
 
 /// User-friendly status that hides technical details
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,6 +50,11 @@ pub enum FriendlyStatus {
     NotInstalled { message: String, download_url: String },
     #[serde(rename = "model_missing")]
     ModelMissing { message: String },
+    /// The installed model no longer matches its pinned digest
+    #[serde(rename = "model_changed")]
+    ModelChanged { message: String },
+    #[serde(rename = "degraded")]
+    Degraded { message: String },
     #[serde(rename = "error")]
     Error { message: String },
     #[serde(rename = "stopped")]
@@ -62,7 +83,27 @@ impl FriendlyStatus {
 
     pub fn model_missing() -> Self {
         Self::ModelMissing {
-            message: "Downloading AI model...".to_string(),
+            message: "AI model needs to be downloaded".to_string(),
+        }
+    }
+
+    pub fn model_changed() -> Self {
+        Self::ModelChanged {
+            message: "AI model was changed outside the app. Re-verify or re-download it".to_string(),
+        }
+    }
+
+    pub fn model_downloading(percent: Option<u64>) -> Self {
+        let message = match percent {
+            Some(percent) => format!("Downloading AI model... {}%", percent),
+            None => "Downloading AI model...".to_string(),
+        };
+        Self::ModelMissing { message }
+    }
+
+    pub fn degraded() -> Self {
+        Self::Degraded {
+            message: "Protection is slow to respond".to_string(),
         }
     }
 
@@ -84,10 +125,14 @@ impl FriendlyStatus {
 pub enum SupervisorState {
     Starting,
     Running,
+    /// Server responds but canary inference keeps failing
+    Degraded,
     Unhealthy,
     Stopped,
     OllamaNotInstalled,
     ModelMissing,
+    /// The model's digest no longer matches the pin
+    ModelChanged,
 }
 
 /// Configuration for the supervisor
@@ -97,6 +142,9 @@ pub struct SupervisorConfig {
     pub health_check_interval: Duration,
     pub startup_timeout: Duration,
     pub default_model: String,
+    pub canary_interval: Duration,
+    pub canary_timeout: Duration,
+    pub max_canary_failures: u32,
 }
 
 impl Default for SupervisorConfig {
@@ -106,16 +154,133 @@ impl Default for SupervisorConfig {
             health_check_interval: HEALTH_CHECK_INTERVAL,
             startup_timeout: STARTUP_TIMEOUT,
             default_model: "phi3:mini".to_string(),
+            canary_interval: CANARY_INTERVAL,
+            canary_timeout: CANARY_TIMEOUT,
+            max_canary_failures: MAX_CANARY_FAILURES,
+        }
+    }
+}
+
+/// The model the supervisor must verify before reporting "Protected"
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelRequirement {
+    /// Exact Ollama tag, e.g. "phi3:mini"
+    pub name: String,
+    /// Pinned digest; `None` pins whatever digest is found first
+    pub digest: Option<String>,
+    /// Pull the model in the background when it is missing
+    pub auto_pull: bool,
+}
+
+/// Result of verifying the required model against `/api/tags`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status")]
+pub enum ModelAvailability {
+    #[serde(rename = "available")]
+    Available { name: String, digest: String },
+    #[serde(rename = "missing")]
+    Missing { name: String },
+    #[serde(rename = "digest_mismatch")]
+    DigestMismatch {
+        name: String,
+        expected: String,
+        found: String,
+    },
+}
+
+/// Progress of a background model pull
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelPullStatus {
+    pub model: String,
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<u64>,
+    pub done: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ModelPullStatus {
+    /// Download progress as a whole percentage, when known
+    pub fn percent(&self) -> Option<u64> {
+        match (self.completed, self.total) {
+            (Some(completed), Some(total)) if total > 0 => Some(completed * 100 / total),
+            _ => None,
         }
     }
 }
 
-/// Ollama Supervisor - manages Ollama lifecycle with auto-recovery
+/// Events the supervisor reports to the rest of the app
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type")]
+pub enum SupervisorEvent {
+    /// First successful verification pinned the model digest
+    #[serde(rename = "model_pinned")]
+    ModelPinned { model: String, digest: String },
+    #[serde(rename = "model_pull")]
+    ModelPull(ModelPullStatus),
+    /// The active profile or a schedule window changed what protection does
+    #[serde(rename = "policy_changed")]
+    PolicyChanged(EffectivePolicy),
+}
+
+/// Failed background pulls of one model, and when to try again
+#[derive(Debug, Clone)]
+struct PullBackoff {
+    model: String,
+    failures: u32,
+    retry_at: Instant,
+}
+
+/// Wait before the next background pull after `failures` in a row
+fn pull_backoff_delay(failures: u32) -> Duration {
+    PULL_BACKOFF_BASE
+        .saturating_mul(2u32.saturating_pow(failures.saturating_sub(1)))
+        .min(PULL_BACKOFF_MAX)
+}
+
+/// Callback invoked for every supervisor event
+pub type SupervisorListener = Arc<dyn Fn(SupervisorEvent) + Send + Sync>;
+
+/// User overrides for how the Ollama server is launched
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OllamaLaunchOptions {
+    /// Explicit path to the `ollama` binary, searched before the defaults
+    pub binary_path: Option<PathBuf>,
+    /// Directory passed to `ollama serve` as `OLLAMA_MODELS`
+    pub models_dir: Option<PathBuf>,
+}
+
+/// Outcome of the most recent canary neutralization
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CanaryResult {
+    pub success: bool,
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub checked_at: i64,
+    pub consecutive_failures: u32,
+}
+
+/// Ollama Supervisor - the single owner of the `ollama serve` process,
+/// with auto-recovery on top of the `OllamaManager` API client
 pub struct OllamaSupervisor {
-    client: reqwest::Client,
+    api: OllamaManager,
     process: Arc<Mutex<Option<Child>>>,
+    launch: StdMutex<OllamaLaunchOptions>,
     state: Arc<Mutex<SupervisorState>>,
     restart_count: AtomicU32,
+    canary_failures: AtomicU32,
+    last_canary: Arc<Mutex<Option<CanaryResult>>>,
+    model: StdMutex<ModelRequirement>,
+    is_pulling: AtomicBool,
+    inference_suspended: AtomicBool,
+    pull_status: Arc<StdMutex<Option<ModelPullStatus>>>,
+    pull_backoff: StdMutex<Option<PullBackoff>>,
+    listener: StdMutex<Option<SupervisorListener>>,
+    policy: StdMutex<EffectivePolicy>,
     is_monitoring: AtomicBool,
     config: SupervisorConfig,
 }
@@ -123,96 +288,121 @@ pub struct OllamaSupervisor {
 impl OllamaSupervisor {
     pub fn new(config: SupervisorConfig) -> Self {
         Self {
-            client: reqwest::Client::builder()
-                .timeout(Duration::from_secs(5))
-                .build()
-                .unwrap_or_default(),
+            api: OllamaManager::new(),
             process: Arc::new(Mutex::new(None)),
+            launch: StdMutex::new(OllamaLaunchOptions::default()),
             state: Arc::new(Mutex::new(SupervisorState::Stopped)),
             restart_count: AtomicU32::new(0),
+            canary_failures: AtomicU32::new(0),
+            last_canary: Arc::new(Mutex::new(None)),
+            model: StdMutex::new(ModelRequirement {
+                name: config.default_model.clone(),
+                digest: None,
+                auto_pull: true,
+            }),
+            is_pulling: AtomicBool::new(false),
+            inference_suspended: AtomicBool::new(false),
+            pull_status: Arc::new(StdMutex::new(None)),
+            pull_backoff: StdMutex::new(None),
+            listener: StdMutex::new(None),
+            policy: StdMutex::new(schedule::evaluate(&Profile::default(), Local::now())),
             is_monitoring: AtomicBool::new(false),
             config,
         }
     }
 
-    /// Detect if Ollama is installed and return its path
-    pub fn find_ollama_binary() -> Option<PathBuf> {
-        #[cfg(target_os = "windows")]
-        {
-            // Check common Windows installation paths
-            let possible_paths = [
-                // User-specific installation (most common)
-                dirs::data_local_dir()
-                    .map(|p| p.join("Programs").join("Ollama").join("ollama.exe")),
-                // System-wide installation
-                Some(PathBuf::from(r"C:\Program Files\Ollama\ollama.exe")),
-                // Alternative paths
-                dirs::home_dir().map(|p| p.join("AppData").join("Local").join("Programs").join("Ollama").join("ollama.exe")),
-            ];
-
-            for path in possible_paths.iter().flatten() {
-                if path.exists() {
-                    log::info!("Found Ollama at: {:?}", path);
-                    return Some(path.clone());
-                }
+    /// Detect if Ollama is installed and return its path.
+    ///
+    /// A user-specified `custom_path` wins when it exists; otherwise the
+    /// standard install locations are searched, then `PATH`.
+    pub fn find_ollama_binary(custom_path: Option<&Path>) -> Option<PathBuf> {
+        if let Some(path) = custom_path {
+            if path.is_file() {
+                log::info!("Using configured Ollama binary: {:?}", path);
+                return Some(path.to_path_buf());
             }
+            log::warn!("Configured Ollama binary {:?} not found, searching defaults", path);
+        }
 
-            // Check PATH as fallback
-            if let Ok(output) = Command::new("where")
-                .arg("ollama")
-                .output()
-            {
-                if output.status.success() {
-                    let path_str = String::from_utf8_lossy(&output.stdout);
-                    if let Some(first_line) = path_str.lines().next() {
-                        let path = PathBuf::from(first_line.trim());
-                        if path.exists() {
-                            log::info!("Found Ollama in PATH: {:?}", path);
-                            return Some(path);
-                        }
-                    }
-                }
+        for path in Self::candidate_paths() {
+            if path.is_file() {
+                log::info!("Found Ollama at: {:?}", path);
+                return Some(path);
             }
+        }
 
-            // Try to find via registry
-            if let Some(path) = Self::find_ollama_from_registry() {
-                return Some(path);
+        if let Some(path) = Self::find_ollama_in_path() {
+            log::info!("Found Ollama in PATH: {:?}", path);
+            return Some(path);
+        }
+
+        // Try to find via registry
+        if let Some(path) = Self::find_ollama_from_registry() {
+            return Some(path);
+        }
+
+        log::warn!("Ollama binary not found");
+        None
+    }
+
+    /// Standard install locations, most likely first
+    fn candidate_paths() -> Vec<PathBuf> {
+        // Where our own installer puts it
+        let mut paths: Vec<PathBuf> = installer::installed_binary_path().into_iter().collect();
+
+        #[cfg(target_os = "windows")]
+        {
+            // User-specific installation (most common)
+            if let Some(dir) = dirs::data_local_dir() {
+                paths.push(dir.join("Programs").join("Ollama").join("ollama.exe"));
             }
+            if let Some(home) = dirs::home_dir() {
+                paths.push(
+                    home.join("AppData")
+                        .join("Local")
+                        .join("Programs")
+                        .join("Ollama")
+                        .join("ollama.exe"),
+                );
+            }
+            // System-wide installation
+            paths.push(PathBuf::from(r"C:\Program Files\Ollama\ollama.exe"));
         }
 
         #[cfg(not(target_os = "windows"))]
         {
-            // Check common Unix paths
-            let possible_paths = [
-                "/usr/local/bin/ollama",
-                "/usr/bin/ollama",
-                "/opt/ollama/ollama",
-            ];
-
-            for path_str in &possible_paths {
-                let path = PathBuf::from(path_str);
-                if path.exists() {
-                    return Some(path);
-                }
-            }
+            paths.push(PathBuf::from("/usr/local/bin/ollama"));
+            paths.push(PathBuf::from("/usr/bin/ollama"));
+            paths.push(PathBuf::from("/opt/ollama/ollama"));
+        }
 
-            // Check PATH
-            if let Ok(output) = Command::new("which")
-                .arg("ollama")
-                .output()
-            {
-                if output.status.success() {
-                    let path_str = String::from_utf8_lossy(&output.stdout);
-                    let path = PathBuf::from(path_str.trim());
-                    if path.exists() {
-                        return Some(path);
-                    }
-                }
-            }
+        #[cfg(target_os = "macos")]
+        {
+            paths.push(PathBuf::from("/opt/homebrew/bin/ollama"));
+            paths.push(PathBuf::from("/Applications/Ollama.app/Contents/Resources/ollama"));
         }
 
-        log::warn!("Ollama binary not found");
-        None
+        paths
+    }
+
+    /// Look up `ollama` on the PATH via `where` / `which`
+    fn find_ollama_in_path() -> Option<PathBuf> {
+        let finder = if cfg!(target_os = "windows") { "where" } else { "which" };
+
+        let mut cmd = Command::new(finder);
+        cmd.arg("ollama").stdout(Stdio::piped()).stderr(Stdio::null());
+
+        #[cfg(target_os = "windows")]
+        cmd.creation_flags(CREATE_NO_WINDOW);
+
+        let output = cmd.output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+
+        let path_str = String::from_utf8_lossy(&output.stdout);
+        let path = PathBuf::from(path_str.lines().next()?.trim());
+        path.is_file().then_some(path)
     }
 
     /// Try to find Ollama from Windows registry
@@ -249,23 +439,34 @@ impl OllamaSupervisor {
         None
     }
 
+    /// Locate the binary this supervisor would launch
+    pub fn ollama_binary(&self) -> Option<PathBuf> {
+        let custom = self.launch_options().binary_path;
+        Self::find_ollama_binary(custom.as_deref())
+    }
+
     /// Check if Ollama is installed
-    pub fn is_installed() -> bool {
-        Self::find_ollama_binary().is_some()
+    pub fn is_installed(&self) -> bool {
+        self.ollama_binary().is_some()
+    }
+
+    /// Current launch overrides
+    pub fn launch_options(&self) -> OllamaLaunchOptions {
+        self.launch.lock().unwrap().clone()
+    }
+
+    /// Replace the launch overrides; they apply on the next start
+    pub fn set_launch_options(&self, options: OllamaLaunchOptions) {
+        let mut launch = self.launch.lock().unwrap();
+        if *launch != options {
+            log::info!("Ollama launch options updated: {:?}", options);
+            *launch = options;
+        }
     }
 
     /// Check if Ollama API is healthy
     pub async fn is_healthy(&self) -> bool {
-        match self
-            .client
-            .get(format!("{}/api/tags", OLLAMA_API_BASE))
-            .timeout(Duration::from_secs(2))
-            .send()
-            .await
-        {
-            Ok(response) => response.status().is_success(),
-            Err(_) => false,
-        }
+        self.api.is_healthy().await
     }
 
     /// Start Ollama with CORS enabled for browser extension
@@ -273,34 +474,53 @@ impl OllamaSupervisor {
         // Check if already running
         if self.is_healthy().await {
             log::info!("Ollama is already running");
-            *self.state.lock().await = SupervisorState::Running;
+            *self.state.lock().await = self.healthy_state();
             return Ok(());
         }
 
         // Find Ollama binary
-        let ollama_path = Self::find_ollama_binary()
+        let ollama_path = self
+            .ollama_binary()
             .ok_or_else(|| "Ollama is not installed".to_string())?;
 
         log::info!("Starting Ollama from: {:?}", ollama_path);
         *self.state.lock().await = SupervisorState::Starting;
 
-        // Build command with CORS enabled
-        let mut cmd = Command::new(&ollama_path);
-        cmd.arg("serve")
-            .env("OLLAMA_ORIGINS", "*") // Allow browser extension CORS
-            .env("OLLAMA_HOST", "127.0.0.1:11434")
-            .stdout(Stdio::null())
-            .stderr(Stdio::null());
+        // Reap a previously spawned server that has since exited
+        let mut process = self.process.lock().await;
+        if let Some(child) = process.as_mut() {
+            if let Ok(Some(status)) = child.try_wait() {
+                log::warn!("Previous Ollama process exited with {}", status);
+                *process = None;
+            }
+        }
+        if process.is_some() {
+            log::info!("Ollama process already spawned, waiting for it to become healthy");
+        } else {
+            // Build command with CORS enabled
+            let mut cmd = Command::new(&ollama_path);
+            cmd.arg("serve")
+                .env("OLLAMA_ORIGINS", "*") // Allow browser extension CORS
+                .env("OLLAMA_HOST", "127.0.0.1:11434")
+                .stdout(Stdio::null())
+                .stderr(Stdio::null());
+
+            if let Some(models_dir) = self.launch_options().models_dir {
+                log::info!("Using Ollama models directory: {:?}", models_dir);
+                cmd.env("OLLAMA_MODELS", models_dir);
+            }
 
-        // Hide console window on Windows
-        #[cfg(target_os = "windows")]
-        cmd.creation_flags(CREATE_NO_WINDOW);
+            // Hide console window on Windows
+            #[cfg(target_os = "windows")]
+            cmd.creation_flags(CREATE_NO_WINDOW);
 
-        let child = cmd
-            .spawn()
-            .map_err(|e| format!("Failed to start Ollama: {}", e))?;
+            let child = cmd
+                .spawn()
+                .map_err(|e| format!("Failed to start Ollama: {}", e))?;
 
-        *self.process.lock().await = Some(child);
+            *process = Some(child);
+        }
+        drop(process);
 
         // Wait for Ollama to be ready
         let timeout_ms = self.config.startup_timeout.as_millis() as u64;
@@ -314,7 +534,7 @@ impl OllamaSupervisor {
                     "Ollama started successfully after {}ms",
                     (i + 1) * check_interval_ms
                 );
-                *self.state.lock().await = SupervisorState::Running;
+                *self.state.lock().await = self.healthy_state();
                 self.restart_count.store(0, Ordering::SeqCst);
                 return Ok(());
             }
@@ -334,6 +554,8 @@ impl OllamaSupervisor {
             child
                 .kill()
                 .map_err(|e| format!("Failed to stop Ollama: {}", e))?;
+            // Reap the process so it doesn't linger as a zombie
+            let _ = child.wait();
             log::info!("Ollama process stopped");
         }
         *self.state.lock().await = SupervisorState::Stopped;
@@ -358,6 +580,7 @@ impl OllamaSupervisor {
         let supervisor = self.clone();
         tokio::spawn(async move {
             log::info!("Starting Ollama health monitor");
+            let mut last_canary_at: Option<Instant> = None;
 
             loop {
                 sleep(supervisor.config.health_check_interval).await;
@@ -388,6 +611,8 @@ impl OllamaSupervisor {
                         }
 
                         supervisor.restart_count.fetch_add(1, Ordering::SeqCst);
+                        // Verify inference soon after a restart
+                        last_canary_at = None;
                     } else {
                         log::error!("Max restart attempts reached, giving up");
                         // Don't spam restarts - wait for user intervention
@@ -398,16 +623,162 @@ impl OllamaSupervisor {
                         log::info!("Ollama recovered, resetting restart count");
                         supervisor.restart_count.store(0, Ordering::SeqCst);
                     }
-                    *supervisor.state.lock().await = SupervisorState::Running;
+
+                    let auto_pull = supervisor.model_requirement().auto_pull
+                        && !supervisor.is_inference_suspended();
+                    let not_ready = match supervisor.verify_model().await {
+                        Ok(ModelAvailability::Available { .. }) => None,
+                        Ok(ModelAvailability::Missing { name }) => {
+                            if auto_pull && supervisor.pull_due(&name) {
+                                supervisor.clone().start_model_pull(name);
+                            }
+                            Some(SupervisorState::ModelMissing)
+                        }
+                        Ok(ModelAvailability::DigestMismatch { name, expected, found }) => {
+                            log::warn!(
+                                "Model {} digest changed (expected {}, found {})",
+                                name,
+                                expected,
+                                found
+                            );
+                            // Pulling restores the published model and re-pins it
+                            if auto_pull && supervisor.pull_due(&name) {
+                                supervisor.clone().start_model_pull(name);
+                            }
+                            Some(SupervisorState::ModelChanged)
+                        }
+                        Err(e) => {
+                            log::warn!("Could not verify model: {}", e);
+                            None
+                        }
+                    };
+
+                    if let Some(state) = not_ready {
+                        *supervisor.state.lock().await = state;
+                        continue;
+                    }
+
+                    *supervisor.state.lock().await = supervisor.healthy_state();
+
+                    // Periodically verify that inference actually works
+                    let canary_due = last_canary_at
+                        .map_or(true, |at| at.elapsed() >= supervisor.config.canary_interval);
+                    if canary_due && !supervisor.is_inference_suspended() {
+                        last_canary_at = Some(Instant::now());
+                        supervisor.check_inference().await;
+                    }
                 }
             }
         });
     }
 
+    /// State to report while the API is reachable
+    fn healthy_state(&self) -> SupervisorState {
+        if self.canary_failures.load(Ordering::SeqCst) >= self.config.max_canary_failures {
+            SupervisorState::Degraded
+        } else {
+            SupervisorState::Running
+        }
+    }
+
+    /// Run a canary neutralization and update the degraded state
+    pub async fn check_inference(&self) -> CanaryResult {
+        let started = Instant::now();
+        let outcome = self.run_canary().await;
+        let latency_ms = started.elapsed().as_millis() as u64;
+
+        let consecutive_failures = match &outcome {
+            Ok(()) => {
+                log::info!("Canary neutralization succeeded in {}ms", latency_ms);
+                self.canary_failures.store(0, Ordering::SeqCst);
+                0
+            }
+            Err(e) => {
+                let failures = self.canary_failures.fetch_add(1, Ordering::SeqCst) + 1;
+                log::warn!(
+                    "Canary neutralization failed ({}/{}): {}",
+                    failures,
+                    self.config.max_canary_failures,
+                    e
+                );
+                failures
+            }
+        };
+
+        // Move between running and degraded without touching other states
+        {
+            let mut state = self.state.lock().await;
+            if matches!(*state, SupervisorState::Running | SupervisorState::Degraded) {
+                let next = self.healthy_state();
+                if *state != next {
+                    log::info!("Supervisor state {:?} -> {:?}", *state, next);
+                    *state = next;
+                }
+            }
+        }
+
+        let result = CanaryResult {
+            success: outcome.is_ok(),
+            latency_ms,
+            error: outcome.err(),
+            checked_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0),
+            consecutive_failures,
+        };
+
+        *self.last_canary.lock().await = Some(result.clone());
+        result
+    }
+
+    /// Neutralize the canary text and validate the output shape
+    async fn run_canary(&self) -> Result<(), String> {
+        let request = GenerateRequest {
+            model: self.model_requirement().name,
+            prompt: neutralization::build_prompt(CANARY_TEXT),
+            stream: false,
+            options: Some(GenerateOptions {
+                temperature: Some(0.0),
+                num_predict: Some(128),
+            }),
+        };
+
+        let generated = self
+            .api
+            .generate_once(&request, self.config.canary_timeout)
+            .await?;
+
+        let output = neutralization::parse_response(&generated.response)?;
+        validate_canary_output(&output)
+    }
+
+    /// Suspend canary inference and auto-pulls while resources are constrained
+    pub fn set_inference_suspended(&self, suspended: bool) {
+        self.inference_suspended.store(suspended, Ordering::SeqCst);
+    }
+
+    pub fn is_inference_suspended(&self) -> bool {
+        self.inference_suspended.load(Ordering::SeqCst)
+    }
+
+    /// Free the memory held by the required model
+    pub async fn unload_model(&self) -> Result<(), String> {
+        let model = self.model_requirement().name;
+        self.api.unload_model(&model).await?;
+        log::info!("Unloaded model {}", model);
+        Ok(())
+    }
+
+    /// Result of the most recent canary check, if one has run
+    pub async fn last_canary(&self) -> Option<CanaryResult> {
+        self.last_canary.lock().await.clone()
+    }
+
     /// Get user-friendly status (hides all technical details)
     pub async fn get_friendly_status(&self) -> FriendlyStatus {
         // Check if installed first
-        if !Self::is_installed() {
+        if !self.is_installed() {
             return FriendlyStatus::not_installed();
         }
 
@@ -419,15 +790,23 @@ impl OllamaSupervisor {
                 // Verify it's actually healthy
                 if self.is_healthy().await {
                     // Check if model is available
-                    match self.check_model_available().await {
-                        Ok(true) => FriendlyStatus::running(),
-                        Ok(false) => FriendlyStatus::model_missing(),
+                    match self.verify_model().await {
+                        Ok(ModelAvailability::Available { .. }) => FriendlyStatus::running(),
+                        Ok(ModelAvailability::Missing { .. }) => self.model_missing_status(),
+                        Ok(ModelAvailability::DigestMismatch { .. }) => self.model_changed_status(),
                         Err(_) => FriendlyStatus::running(), // Assume it's fine if we can't check
                     }
                 } else {
                     FriendlyStatus::error("Connection issue. Restarting...")
                 }
             }
+            SupervisorState::Degraded => {
+                if self.is_healthy().await {
+                    FriendlyStatus::degraded()
+                } else {
+                    FriendlyStatus::error("Connection issue. Restarting...")
+                }
+            }
             SupervisorState::Unhealthy => {
                 let restart_count = self.restart_count.load(Ordering::SeqCst);
                 if restart_count >= self.config.max_restart_attempts {
@@ -438,37 +817,240 @@ impl OllamaSupervisor {
             }
             SupervisorState::Stopped => FriendlyStatus::stopped(),
             SupervisorState::OllamaNotInstalled => FriendlyStatus::not_installed(),
-            SupervisorState::ModelMissing => FriendlyStatus::model_missing(),
+            SupervisorState::ModelMissing => self.model_missing_status(),
+            SupervisorState::ModelChanged => self.model_changed_status(),
+        }
+    }
+
+    /// Changed-model status; a pull in progress replaces the model
+    fn model_changed_status(&self) -> FriendlyStatus {
+        if self.is_pulling.load(Ordering::SeqCst) {
+            self.model_missing_status()
+        } else {
+            FriendlyStatus::model_changed()
+        }
+    }
+
+    /// Missing-model status, including download progress while pulling
+    fn model_missing_status(&self) -> FriendlyStatus {
+        if self.is_pulling.load(Ordering::SeqCst) {
+            let percent = self.pull_status().and_then(|status| status.percent());
+            FriendlyStatus::model_downloading(percent)
+        } else {
+            FriendlyStatus::model_missing()
+        }
+    }
+
+    /// Verify the required model by exact tag and pinned digest
+    pub async fn verify_model(&self) -> Result<ModelAvailability, String> {
+        let models = self.api.list_models().await?;
+        let requirement = self.model_requirement();
+        let wanted = normalize_model_tag(&requirement.name);
+
+        let installed = models
+            .into_iter()
+            .find(|m| normalize_model_tag(&m.name) == wanted);
+
+        let Some(installed) = installed else {
+            return Ok(ModelAvailability::Missing {
+                name: requirement.name,
+            });
+        };
+
+        let found = normalize_digest(&installed.digest);
+        match requirement.digest.as_deref().map(normalize_digest) {
+            Some(expected) if expected != found => Ok(ModelAvailability::DigestMismatch {
+                name: requirement.name,
+                expected,
+                found,
+            }),
+            Some(_) => Ok(ModelAvailability::Available {
+                name: requirement.name,
+                digest: found,
+            }),
+            None => {
+                log::info!("Pinning model {} to digest {}", requirement.name, found);
+                self.pin_model_digest(&requirement.name, &found);
+                Ok(ModelAvailability::Available {
+                    name: requirement.name,
+                    digest: found,
+                })
+            }
+        }
+    }
+
+    /// Record the digest for the required model and notify the listener
+    fn pin_model_digest(&self, name: &str, digest: &str) {
+        {
+            let mut model = self.model.lock().unwrap();
+            if model.name != name {
+                return;
+            }
+            model.digest = Some(digest.to_string());
+        }
+        self.emit(SupervisorEvent::ModelPinned {
+            model: name.to_string(),
+            digest: digest.to_string(),
+        });
+    }
+
+    /// Current model requirement
+    pub fn model_requirement(&self) -> ModelRequirement {
+        self.model.lock().unwrap().clone()
+    }
+
+    /// Replace the model requirement (e.g. after settings change)
+    pub fn set_model_requirement(&self, requirement: ModelRequirement) {
+        let mut model = self.model.lock().unwrap();
+        if *model != requirement {
+            log::info!("Supervisor now requires model {}", requirement.name);
+            *model = requirement;
+            self.reset_pull_backoff();
+        }
+    }
+
+    /// Whether a background pull of `model` may start, or is still backing
+    /// off after failures
+    fn pull_due(&self, model: &str) -> bool {
+        match &*self.pull_backoff.lock().unwrap() {
+            Some(backoff) if backoff.model == model => Instant::now() >= backoff.retry_at,
+            _ => true,
+        }
+    }
+
+    /// Allow background pulls again right away, e.g. after the user retried
+    pub fn reset_pull_backoff(&self) {
+        *self.pull_backoff.lock().unwrap() = None;
+    }
+
+    fn record_pull_failure(&self, model: &str) {
+        let mut backoff = self.pull_backoff.lock().unwrap();
+        let failures = match &*backoff {
+            Some(previous) if previous.model == model => previous.failures + 1,
+            _ => 1,
+        };
+        let delay = pull_backoff_delay(failures);
+        log::warn!(
+            "Background pull of {} failed {} time(s); next try in {}s",
+            model,
+            failures,
+            delay.as_secs()
+        );
+        *backoff = Some(PullBackoff {
+            model: model.to_string(),
+            failures,
+            retry_at: Instant::now() + delay,
+        });
+    }
+
+    /// Forget the pinned digest so the next verification re-pins it
+    pub fn clear_model_pin(&self) {
+        self.model.lock().unwrap().digest = None;
+    }
+
+    /// Accept the model as installed now: drop the old pin and verify again,
+    /// which pins the current digest
+    pub async fn repin_model(&self) -> Result<ModelAvailability, String> {
+        self.clear_model_pin();
+        let availability = self.verify_model().await?;
+        if matches!(availability, ModelAvailability::Available { .. }) {
+            let mut state = self.state.lock().await;
+            if *state == SupervisorState::ModelChanged {
+                *state = self.healthy_state();
+            }
+        }
+        Ok(availability)
+    }
+
+    /// Register the callback that receives supervisor events
+    pub fn set_listener(&self, listener: SupervisorListener) {
+        *self.listener.lock().unwrap() = Some(listener);
+    }
+
+    fn emit(&self, event: SupervisorEvent) {
+        let listener = self.listener.lock().unwrap().clone();
+        if let Some(listener) = listener {
+            listener(event);
         }
     }
 
-    /// Check if the default model is available
-    async fn check_model_available(&self) -> Result<bool, String> {
-        let response = self
-            .client
-            .get(format!("{}/api/tags", OLLAMA_API_BASE))
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
+    /// Policy protection currently follows
+    pub fn effective_policy(&self) -> EffectivePolicy {
+        self.policy.lock().unwrap().clone()
+    }
 
-        #[derive(Deserialize)]
-        struct TagsResponse {
-            models: Option<Vec<ModelInfo>>,
+    /// Record the policy now in force; notifies the listener and returns
+    /// true when it changed
+    pub fn set_effective_policy(&self, policy: EffectivePolicy) -> bool {
+        {
+            let mut current = self.policy.lock().unwrap();
+            if *current == policy {
+                return false;
+            }
+            *current = policy.clone();
         }
+        self.emit(SupervisorEvent::PolicyChanged(policy));
+        true
+    }
 
-        #[derive(Deserialize)]
-        struct ModelInfo {
-            name: String,
+    /// Latest progress of the background model pull, if any
+    pub fn pull_status(&self) -> Option<ModelPullStatus> {
+        self.pull_status.lock().unwrap().clone()
+    }
+
+    /// Pull a model in the background, reporting progress as events
+    pub fn start_model_pull(self: Arc<Self>, model: String) {
+        if self.is_pulling.swap(true, Ordering::SeqCst) {
+            return;
         }
 
-        let tags: TagsResponse = response.json().await.map_err(|e| e.to_string())?;
+        log::info!("Pulling model {} in background", model);
 
-        let models = tags.models.unwrap_or_default();
-        let has_model = models
-            .iter()
-            .any(|m| m.name.starts_with(&self.config.default_model.replace(":mini", "")));
+        tokio::spawn(async move {
+            let supervisor = self.clone();
+            let model_for_progress = model.clone();
+            let result = self
+                .api
+                .pull_model(&model, move |progress: PullProgress| {
+                    let status = ModelPullStatus {
+                        model: model_for_progress.clone(),
+                        status: progress.status,
+                        completed: progress.completed,
+                        total: progress.total,
+                        done: false,
+                        error: None,
+                    };
+                    *supervisor.pull_status.lock().unwrap() = Some(status.clone());
+                    supervisor.emit(SupervisorEvent::ModelPull(status));
+                })
+                .await;
+
+            let status = ModelPullStatus {
+                model: model.clone(),
+                status: if result.is_ok() { "success" } else { "failed" }.to_string(),
+                completed: None,
+                total: None,
+                done: true,
+                error: result.as_ref().err().cloned(),
+            };
+
+            match &result {
+                Ok(()) => {
+                    log::info!("Model {} pulled successfully", model);
+                    self.reset_pull_backoff();
+                    // A freshly pulled model gets a fresh pin
+                    self.clear_model_pin();
+                }
+                Err(e) => {
+                    log::error!("Failed to pull model {}: {}", model, e);
+                    self.record_pull_failure(&model);
+                }
+            }
 
-        Ok(has_model || !models.is_empty())
+            *self.pull_status.lock().unwrap() = Some(status.clone());
+            self.emit(SupervisorEvent::ModelPull(status));
+            self.is_pulling.store(false, Ordering::SeqCst);
+        });
     }
 
     /// Get the current internal state
@@ -482,6 +1064,33 @@ impl OllamaSupervisor {
     }
 }
 
+/// Treat an untagged model name as ":latest", like Ollama does
+fn normalize_model_tag(name: &str) -> String {
+    let name = name.trim();
+    let base = name.rsplit('/').next().unwrap_or(name);
+    if base.contains(':') {
+        name.to_string()
+    } else {
+        format!("{}:latest", name)
+    }
+}
+
+/// Digests may be reported with or without the "sha256:" prefix
+fn normalize_digest(digest: &str) -> String {
+    digest.trim().trim_start_matches("sha256:").to_lowercase()
+}
+
+/// Check that a canary result looks like a real neutralization
+fn validate_canary_output(output: &NeutralizationOutput) -> Result<(), String> {
+    if output.neutralized.trim().is_empty() {
+        return Err("Canary returned empty neutralized text".to_string());
+    }
+    if !(0..=10).contains(&output.severity) {
+        return Err(format!("Canary severity out of range: {}", output.severity));
+    }
+    Ok(())
+}
+
 impl Default for OllamaSupervisor {
     fn default() -> Self {
         Self::new(SupervisorConfig::default())
@@ -507,4 +1116,68 @@ mod tests {
         assert!(json.contains("not_installed"));
         assert!(json.contains("ollama.ai"));
     }
+
+    #[test]
+    fn test_degraded_status_serialization() {
+        let status = FriendlyStatus::degraded();
+        let json = serde_json::to_string(&status).unwrap();
+        assert!(json.contains("\"status\":\"degraded\""));
+    }
+
+    #[test]
+    fn test_model_changed_status_serialization() {
+        let json = serde_json::to_string(&FriendlyStatus::model_changed()).unwrap();
+        assert!(json.contains("\"status\":\"model_changed\""));
+        assert!(!json.contains("downloaded"));
+    }
+
+    #[test]
+    fn test_pull_backoff_grows_and_caps() {
+        assert_eq!(pull_backoff_delay(1), PULL_BACKOFF_BASE);
+        assert_eq!(pull_backoff_delay(3), PULL_BACKOFF_BASE * 4);
+        assert_eq!(pull_backoff_delay(40), PULL_BACKOFF_MAX);
+
+        let supervisor = OllamaSupervisor::new(SupervisorConfig::default());
+        assert!(supervisor.pull_due("phi3:mini"));
+        supervisor.record_pull_failure("phi3:mini");
+        assert!(!supervisor.pull_due("phi3:mini"));
+        assert!(supervisor.pull_due("llama3:8b"));
+        supervisor.reset_pull_backoff();
+        assert!(supervisor.pull_due("phi3:mini"));
+    }
+
+    #[test]
+    fn test_model_tag_matching() {
+        assert_eq!(normalize_model_tag("llama3"), "llama3:latest");
+        assert_eq!(normalize_model_tag("phi3:mini"), "phi3:mini");
+        assert_eq!(
+            normalize_model_tag("registry.local:5000/phi3"),
+            "registry.local:5000/phi3:latest"
+        );
+        // "phi3" must not match "phi3:mini"
+        assert_ne!(normalize_model_tag("phi3"), normalize_model_tag("phi3:mini"));
+        assert_eq!(normalize_digest("sha256:ABC123"), normalize_digest("abc123"));
+    }
+
+    #[test]
+    fn test_validate_canary_output() {
+        let valid = NeutralizationOutput {
+            neutralized: "Some people want to share this news.".to_string(),
+            techniques: vec!["urgency".to_string()],
+            severity: 6,
+        };
+        assert!(validate_canary_output(&valid).is_ok());
+
+        let empty = NeutralizationOutput {
+            neutralized: "   ".to_string(),
+            ..valid.clone()
+        };
+        assert!(validate_canary_output(&empty).is_err());
+
+        let out_of_range = NeutralizationOutput {
+            severity: 42,
+            ..valid
+        };
+        assert!(validate_canary_output(&out_of_range).is_err());
+    }
 }